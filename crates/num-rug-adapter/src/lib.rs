@@ -117,6 +117,27 @@ impl Integer {
     pub fn gcd(&self, other: &Self) -> Self {
         Integer(num_integer::Integer::gcd(&self.0, &other.0))
     }
+
+    #[inline]
+    pub fn significant_bits(&self) -> u32 {
+        self.0.bits() as u32
+    }
+
+    pub fn find_one(&self, start: u32) -> Option<u32> {
+        let bytes = self.0.magnitude().to_bytes_le();
+
+        for (i, byte) in bytes.into_iter().enumerate() {
+            for bit in 0..8 {
+                let index = (i * 8 + bit) as u32;
+
+                if index >= start && byte & (1 << bit) != 0 {
+                    return Some(index);
+                }
+            }
+        }
+
+        None
+    }
 }
 
 impl From<&Integer> for Integer {
@@ -283,12 +283,14 @@ pub type OpDir = IndexMap<OpDirKey, OpDirValue>;
 #[derive(Debug, Clone, Copy)]
 pub struct MachineFlags {
     pub double_quotes: DoubleQuotes,
+    pub char_conversion: bool,
 }
 
 impl Default for MachineFlags {
     fn default() -> Self {
         MachineFlags {
             double_quotes: DoubleQuotes::default(),
+            char_conversion: false,
         }
     }
 }
@@ -341,10 +343,10 @@ pub enum ArithmeticError {
 pub enum ParserError {
     BackQuotedString(usize, usize),
     UnexpectedChar(char, usize, usize),
-    UnexpectedEOF,
+    UnexpectedEOF(usize, usize),
     IO(IOError),
     IncompleteReduction(usize, usize),
-    InvalidSingleQuotedCharacter(char),
+    InvalidSingleQuotedCharacter(char, usize, usize),
     MissingQuote(usize, usize),
     NonPrologChar(usize, usize),
     ParseBigInt(usize, usize),
@@ -356,7 +358,9 @@ impl ParserError {
         match self {
             &ParserError::BackQuotedString(line_num, col_num)
             | &ParserError::UnexpectedChar(_, line_num, col_num)
+            | &ParserError::UnexpectedEOF(line_num, col_num)
             | &ParserError::IncompleteReduction(line_num, col_num)
+            | &ParserError::InvalidSingleQuotedCharacter(_, line_num, col_num)
             | &ParserError::MissingQuote(line_num, col_num)
             | &ParserError::NonPrologChar(line_num, col_num)
             | &ParserError::ParseBigInt(line_num, col_num)
@@ -369,7 +373,7 @@ impl ParserError {
         match self {
             ParserError::BackQuotedString(..) => "back_quoted_string",
             ParserError::UnexpectedChar(..) => "unexpected_char",
-            ParserError::UnexpectedEOF => "unexpected_end_of_file",
+            ParserError::UnexpectedEOF(..) => "unexpected_end_of_file",
             ParserError::IncompleteReduction(..) => "incomplete_reduction",
             ParserError::InvalidSingleQuotedCharacter(..) => "invalid_single_quoted_character",
             ParserError::IO(_) => "input_output_error",
@@ -5,16 +5,26 @@ use ordered_float::*;
 use crate::ast::*;
 use crate::tabled_rc::*;
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
 use std::io::Read;
 use std::rc::Rc;
 
+// bytes that fail to decode as UTF-8 are reported as such rather than
+// as a premature end of file.
+fn read_error(err: &std::io::Error, line_num: usize, col_num: usize) -> ParserError {
+    match ParserError::from(err) {
+        ParserError::Utf8Error(..) => ParserError::Utf8Error(line_num, col_num),
+        _ => ParserError::UnexpectedEOF(line_num, col_num),
+    }
+}
+
 macro_rules! is_not_eof {
     ($c:expr) => {
         match $c {
             Ok(c) => c,
-            Err($crate::ast::ParserError::UnexpectedEOF) => return Ok(true),
+            Err($crate::ast::ParserError::UnexpectedEOF(..)) => return Ok(true),
             Err(e) => return Err(e),
         }
     };
@@ -66,6 +76,10 @@ pub struct Lexer<'a, R: Read> {
     pub(crate) flags: MachineFlags,
     pub(crate) line_num: usize,
     pub(crate) col_num: usize,
+    // the conversions of char_conversion/2. they apply to the unquoted
+    // characters read while the char_conversion flag is on.
+    pub(crate) char_conversions: Rc<HashMap<char, char>>,
+    quoted: bool,
 }
 
 impl<'a, R: Read + fmt::Debug> fmt::Debug for Lexer<'a, R> {
@@ -91,9 +105,19 @@ impl<'a, R: Read> Lexer<'a, R> {
             reader: src,
             line_num: 0,
             col_num: 0,
+            char_conversions: Rc::new(HashMap::new()),
+            quoted: false,
         }
     }
 
+    fn convert_char(&self, c: char) -> char {
+        if self.quoted || !self.flags.char_conversion {
+            return c;
+        }
+
+        self.char_conversions.get(&c).cloned().unwrap_or(c)
+    }
+
     fn return_char(&mut self, c: char) {
         if new_line_char!(c) {
             self.line_num -= 1;
@@ -104,17 +128,20 @@ impl<'a, R: Read> Lexer<'a, R> {
     }
 
     fn skip_char(&mut self) -> Result<char, ParserError> {
-        if let Some(Ok(c)) = self.reader.next() {
-            self.col_num += 1;
+        match self.reader.next() {
+            Some(Ok(c)) => {
+                let c = self.convert_char(c);
+                self.col_num += 1;
+
+                if new_line_char!(c) {
+                    self.line_num += 1;
+                    self.col_num = 0;
+                }
 
-            if new_line_char!(c) {
-                self.line_num += 1;
-                self.col_num = 0;
+                Ok(c)
             }
-
-            Ok(c)
-        } else {
-            Err(ParserError::UnexpectedEOF)
+            Some(Err(e)) => Err(read_error(&e, self.line_num, self.col_num)),
+            None => Err(ParserError::UnexpectedEOF(self.line_num, self.col_num)),
         }
     }
 
@@ -139,9 +166,12 @@ impl<'a, R: Read> Lexer<'a, R> {
     }
 
     pub fn lookahead_char(&mut self) -> Result<char, ParserError> {
+        let (line_num, col_num) = (self.line_num, self.col_num);
+
         match self.reader.peek() {
-            Some(&Ok(c)) => Ok(c),
-            _ => Err(ParserError::UnexpectedEOF),
+            Some(&Ok(c)) => Ok(self.convert_char(c)),
+            Some(Err(e)) => Err(read_error(e, line_num, col_num)),
+            None => Err(ParserError::UnexpectedEOF(line_num, col_num)),
         }
     }
 
@@ -230,12 +260,14 @@ impl<'a, R: Read> Lexer<'a, R> {
 
         if back_quote_char!(c) {
             self.skip_char()?;
+            self.quoted = true;
 
             let mut token = String::new();
             consume_chars_with!(token, self.get_back_quoted_item());
 
             if back_quote_char!(self.lookahead_char()?) {
                 self.skip_char()?;
+                self.quoted = false;
                 Ok(token)
             } else {
                 Err(ParserError::MissingQuote(self.line_num, self.col_num))
@@ -411,10 +443,12 @@ impl<'a, R: Read> Lexer<'a, R> {
         let mut token = String::new();
 
         self.skip_char()?;
+        self.quoted = true;
         consume_chars_with!(token, self.get_double_quoted_item());
 
         if double_quote_char!(self.lookahead_char()?) {
             self.skip_char()?;
+            self.quoted = false;
             Ok(token)
         } else {
             Err(ParserError::MissingQuote(self.line_num, self.col_num))
@@ -522,11 +556,13 @@ impl<'a, R: Read> Lexer<'a, R> {
             token.push(self.skip_char()?);
         } else if single_quote_char!(c) {
             self.skip_char()?;
+            self.quoted = true;
 
             consume_chars_with!(token, self.get_single_quoted_item());
 
             if single_quote_char!(self.lookahead_char()?) {
                 self.skip_char()?;
+                self.quoted = false;
 
                 if !token.is_empty() && token.chars().nth(1).is_none() {
                     if let Some(c) = token.chars().next() {
@@ -536,6 +572,8 @@ impl<'a, R: Read> Lexer<'a, R> {
             } else {
                 return Err(ParserError::InvalidSingleQuotedCharacter(
                     self.lookahead_char()?,
+                    self.line_num,
+                    self.col_num,
                 ));
             }
         } else {
@@ -709,6 +747,7 @@ impl<'a, R: Read> Lexer<'a, R> {
                     })
                 } else if single_quote_char!(c) {
                     self.skip_char()?;
+                    self.quoted = true;
 
                     if backslash_char!(self.lookahead_char()?) {
                         self.skip_char()?;
@@ -716,6 +755,7 @@ impl<'a, R: Read> Lexer<'a, R> {
                         if new_line_char!(self.lookahead_char()?) {
                             self.return_char('\\');
                             self.return_char('\'');
+                            self.quoted = false;
 
                             return Ok(Token::Constant(Constant::Fixnum(0)));
                         } else {
@@ -723,7 +763,10 @@ impl<'a, R: Read> Lexer<'a, R> {
                         }
                     }
 
-                    self.get_single_quoted_char()
+                    let quoted_char = self.get_single_quoted_char();
+                    self.quoted = false;
+
+                    quoted_char
                         .map(|c| Token::Constant(Constant::Fixnum(c as isize)))
                         .or_else(|_| {
                             self.return_char(c);
@@ -797,6 +840,9 @@ impl<'a, R: Read> Lexer<'a, R> {
     }
 
     pub fn next_token(&mut self) -> Result<Token, ParserError> {
+        // a quoted token ended by an error may leave quoted set.
+        self.quoted = false;
+
         let layout_inserted = self.scan_for_layout()?;
         let cr = self.lookahead_char();
 
@@ -836,7 +882,7 @@ impl<'a, R: Read> Lexer<'a, R> {
 
                             return Ok(Token::End);
                         }
-                        Err(ParserError::UnexpectedEOF) => {
+                        Err(ParserError::UnexpectedEOF(..)) => {
                             return Ok(Token::End);
                         }
                         _ => {
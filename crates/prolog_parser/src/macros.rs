@@ -231,6 +231,13 @@ macro_rules! space_char {
     };
 }
 
+#[macro_export]
+macro_rules! white_char {
+    ($c: expr) => {
+        $c == ' ' || $c == '\t'
+    };
+}
+
 #[macro_export]
 macro_rules! symbolic_control_char {
     ($c: expr) => {
@@ -7,6 +7,7 @@ use ordered_float::OrderedFloat;
 use crate::rug::ops::NegAssign;
 
 use std::cell::Cell;
+use std::collections::HashMap;
 use std::io::Read;
 use std::mem::swap;
 use std::rc::Rc;
@@ -208,7 +209,7 @@ fn read_tokens<R: Read>(lexer: &mut Lexer<R>) -> Result<Vec<Token>, ParserError>
                     break;
                 }
             }
-            Err(ParserError::UnexpectedEOF) if !tokens.is_empty() => {
+            Err(ParserError::UnexpectedEOF(..)) if !tokens.is_empty() => {
                 return Err(ParserError::IncompleteReduction(lexer.line_num, lexer.col_num));
             }
             Err(e) => {
@@ -256,6 +257,11 @@ impl<'a, R: Read> Parser<'a, R> {
         self.lexer.atom_tbl = atom_tbl;
     }
 
+    #[inline]
+    pub fn set_char_conversions(&mut self, char_conversions: Rc<HashMap<char, char>>) {
+        self.lexer.char_conversions = char_conversions;
+    }
+
     fn get_term_name(&mut self, td: TokenDesc) -> Option<(ClauseName, Option<SharedOpDesc>)> {
         match td.tt {
             TokenType::HeadTailSeparator => Some((
@@ -756,7 +762,10 @@ impl<'a, R: Read> Parser<'a, R> {
         }) = get_op_desc(name.clone(), op_dir)
         {
             if (pre > 0 && inf + post > 0) || is_negate!(spec) {
-                match self.tokens.last().ok_or(ParserError::UnexpectedEOF)? {
+                match self.tokens.last().ok_or(ParserError::UnexpectedEOF(
+                    self.lexer.line_num,
+                    self.lexer.col_num,
+                ))? {
                     // do this when layout hasn't been inserted,
                     // ie. why we don't match on Token::Open.
                     Token::OpenCT => {
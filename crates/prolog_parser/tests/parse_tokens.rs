@@ -110,3 +110,13 @@ fn comment_then_eof() -> Result<(), ParserError> {
     assert!(read_all_tokens("% only a comment").is_err());
     Ok(())
 }
+
+#[test]
+fn eof_in_quoted_atom_has_position() {
+    match read_all_tokens("foo.\n'abc") {
+        Err(err @ ParserError::UnexpectedEOF(..)) => {
+            assert_eq!(err.line_and_col_num().map(|(line, _)| line), Some(1));
+        }
+        result => panic!("expected an unexpected end of file, got {:?}", result),
+    }
+}
@@ -166,6 +166,8 @@ impl<'a> ArithmeticEvaluator<'a> {
             "ceiling" => Ok(ArithmeticInstruction::Ceiling(a1, t)),
             "floor" => Ok(ArithmeticInstruction::Floor(a1, t)),
             "sign" => Ok(ArithmeticInstruction::Sign(a1, t)),
+            "msb" => Ok(ArithmeticInstruction::Msb(a1, t)),
+            "lsb" => Ok(ArithmeticInstruction::Lsb(a1, t)),
             "\\" => Ok(ArithmeticInstruction::BitwiseComplement(a1, t)),
             _ => Err(ArithmeticError::NonEvaluableFunctor(
                 Constant::Atom(name, None),
@@ -200,7 +202,8 @@ impl<'a> ArithmeticEvaluator<'a> {
             "mod" => Ok(ArithmeticInstruction::Mod(a1, a2, t)),
             "rem" => Ok(ArithmeticInstruction::Rem(a1, a2, t)),
             "gcd" => Ok(ArithmeticInstruction::Gcd(a1, a2, t)),
-            "atan2" => Ok(ArithmeticInstruction::ATan2(a1, a2, t)),
+            "atan2" | "atan" => Ok(ArithmeticInstruction::ATan2(a1, a2, t)),
+            "log" => Ok(ArithmeticInstruction::LogBase(a1, a2, t)),
             _ => Err(ArithmeticError::NonEvaluableFunctor(
                 Constant::Atom(name, None),
                 2,
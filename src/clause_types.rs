@@ -3,7 +3,6 @@ use prolog_parser::{clause_name, temp_v};
 
 use crate::forms::Number;
 use crate::machine::machine_indices::*;
-use crate::rug::rand::RandState;
 
 use ref_thread_local::{ref_thread_local, RefThreadLocal};
 
@@ -73,6 +72,7 @@ pub(crate) enum InlinedClauseType {
     CompareNumber(CompareNumberQT, ArithmeticTerm, ArithmeticTerm),
     IsAtom(RegType),
     IsAtomic(RegType),
+    IsCallable(RegType),
     IsCompound(RegType),
     IsInteger(RegType),
     IsNumber(RegType),
@@ -82,10 +82,6 @@ pub(crate) enum InlinedClauseType {
     IsVar(RegType),
 }
 
-ref_thread_local! {
-    pub(crate)static managed RANDOM_STATE: RandState<'static> = RandState::new();
-}
-
 ref_thread_local! {
     pub(crate)static managed CLAUSE_TYPE_FORMS: BTreeMap<(&'static str, usize), ClauseType> = {
         let mut m = BTreeMap::new();
@@ -103,6 +99,7 @@ ref_thread_local! {
         m.insert(("=\\=", 2), ClauseType::Inlined(InlinedClauseType::CompareNumber(CompareNumberQT::NotEqual, ar_reg!(r1), ar_reg!(r2))));
         m.insert(("atom", 1), ClauseType::Inlined(InlinedClauseType::IsAtom(r1)));
         m.insert(("atomic", 1), ClauseType::Inlined(InlinedClauseType::IsAtomic(r1)));
+        m.insert(("callable", 1), ClauseType::Inlined(InlinedClauseType::IsCallable(r1)));
         m.insert(("compound", 1), ClauseType::Inlined(InlinedClauseType::IsCompound(r1)));
         m.insert(("integer", 1), ClauseType::Inlined(InlinedClauseType::IsInteger(r1)));
         m.insert(("number", 1), ClauseType::Inlined(InlinedClauseType::IsNumber(r1)));
@@ -137,6 +134,7 @@ impl InlinedClauseType {
             &InlinedClauseType::CompareNumber(qt, ..) => qt.name(),
             &InlinedClauseType::IsAtom(..) => "atom",
             &InlinedClauseType::IsAtomic(..) => "atomic",
+            &InlinedClauseType::IsCallable(..) => "callable",
             &InlinedClauseType::IsCompound(..) => "compound",
             &InlinedClauseType::IsNumber(..) => "number",
             &InlinedClauseType::IsInteger(..) => "integer",
@@ -158,6 +156,7 @@ pub(crate) enum SystemClauseType {
     CharCode,
     CharType,
     CharsToNumber,
+    ClauseLocations,
     CodesToNumber,
     CopyTermWithoutAttrVars,
     CheckCutPoint,
@@ -187,6 +186,7 @@ pub(crate) enum SystemClauseType {
     FetchGlobalVar,
     FirstStream,
     FlushOutput,
+    Foreign(usize),
     GetByte,
     GetChar,
     GetNChars,
@@ -214,6 +214,7 @@ pub(crate) enum SystemClauseType {
     InstallInferenceCounter,
     LiftedHeapLength,
     LoadLibraryAsStream,
+    LibraryPaths,
     ModuleExists,
     NextEP,
     NoSuchPredicate,
@@ -221,6 +222,7 @@ pub(crate) enum SystemClauseType {
     NumberToCodes,
     OpDeclaration,
     Open,
+    OpenCharsStream,
     NextStream,
     PartialStringTail,
     PeekByte,
@@ -254,17 +256,24 @@ pub(crate) enum SystemClauseType {
     GetCurrentBlock,
     GetCutPoint,
     GetDoubleQuotes,
+    GetCharConversionFlag,
     InstallNewBlock,
     Maybe,
     CpuNow,
     CurrentTime,
+    GetTime,
+    TimestampToTime,
     QuotedToken,
     ReadTermFromChars,
     ResetBlock,
     ReturnFromVerifyAttr,
+    SetArg,
     SetBall,
     SetCutPointByDefault(RegType),
     SetDoubleQuotes,
+    SetCharConversionFlag,
+    CharConversion,
+    CharConversions,
     SetSeed,
     SkipMaxList,
     Sleep,
@@ -301,6 +310,8 @@ pub(crate) enum SystemClauseType {
     GetEnv,
     SetEnv,
     UnsetEnv,
+    Environ,
+    Shell,
     PID,
     CharsBase64,
     DevourWhitespace,
@@ -323,6 +334,7 @@ impl SystemClauseType {
             &SystemClauseType::CharCode => clause_name!("$char_code"),
             &SystemClauseType::CharType => clause_name!("$char_type"),
             &SystemClauseType::CharsToNumber => clause_name!("$chars_to_number"),
+            &SystemClauseType::ClauseLocations => clause_name!("$clause_locations"),
             &SystemClauseType::CheckCutPoint => clause_name!("$check_cp"),
             &SystemClauseType::CodesToNumber => clause_name!("$codes_to_number"),
             &SystemClauseType::CopyTermWithoutAttrVars => {
@@ -442,6 +454,7 @@ impl SystemClauseType {
             &SystemClauseType::FetchGlobalVar => clause_name!("$fetch_global_var"),
             &SystemClauseType::FirstStream => clause_name!("$first_stream"),
             &SystemClauseType::FlushOutput => clause_name!("$flush_output"),
+            &SystemClauseType::Foreign(_) => clause_name!("$foreign_rust"),
             &SystemClauseType::GetByte => clause_name!("$get_byte"),
             &SystemClauseType::GetChar => clause_name!("$get_char"),
             &SystemClauseType::GetNChars => clause_name!("$get_n_chars"),
@@ -471,11 +484,15 @@ impl SystemClauseType {
             &SystemClauseType::LookupDBRef => clause_name!("$lookup_db_ref"),
             &SystemClauseType::LookupOpDBRef => clause_name!("$lookup_op_db_ref"),
             &SystemClauseType::GetDoubleQuotes => clause_name!("$get_double_quotes"),
+            &SystemClauseType::GetCharConversionFlag => {
+                clause_name!("$get_char_conversion_flag")
+            }
             //          &SystemClauseType::GetModuleClause => clause_name!("$get_module_clause"),
             &SystemClauseType::GetSCCCleaner => clause_name!("$get_scc_cleaner"),
             &SystemClauseType::Halt => clause_name!("$halt"),
             &SystemClauseType::HeadIsDynamic => clause_name!("$head_is_dynamic"),
             &SystemClauseType::Open => clause_name!("$open"),
+            &SystemClauseType::OpenCharsStream => clause_name!("$open_chars_stream"),
             &SystemClauseType::OpDeclaration => clause_name!("$op"),
             &SystemClauseType::InstallSCCCleaner => clause_name!("$install_scc_cleaner"),
             &SystemClauseType::InstallInferenceCounter => {
@@ -490,6 +507,8 @@ impl SystemClauseType {
             &SystemClauseType::Maybe => clause_name!("maybe"),
             &SystemClauseType::CpuNow => clause_name!("$cpu_now"),
             &SystemClauseType::CurrentTime => clause_name!("$current_time"),
+            &SystemClauseType::GetTime => clause_name!("$get_time"),
+            &SystemClauseType::TimestampToTime => clause_name!("$timestamp_to_time"),
             // &SystemClauseType::ModuleAssertDynamicPredicateToFront => {
             //     clause_name!("$module_asserta")
             // }
@@ -549,9 +568,15 @@ impl SystemClauseType {
             &SystemClauseType::ResetBlock => clause_name!("$reset_block"),
             &SystemClauseType::ResetContinuationMarker => clause_name!("$reset_cont_marker"),
             &SystemClauseType::ReturnFromVerifyAttr => clause_name!("$return_from_verify_attr"),
+            &SystemClauseType::SetArg => clause_name!("$setarg"),
             &SystemClauseType::SetBall => clause_name!("$set_ball"),
             &SystemClauseType::SetCutPointByDefault(_) => clause_name!("$set_cp_by_default"),
             &SystemClauseType::SetDoubleQuotes => clause_name!("$set_double_quotes"),
+            &SystemClauseType::SetCharConversionFlag => {
+                clause_name!("$set_char_conversion_flag")
+            }
+            &SystemClauseType::CharConversion => clause_name!("$char_conversion"),
+            &SystemClauseType::CharConversions => clause_name!("$char_conversions"),
             &SystemClauseType::SkipMaxList => clause_name!("$skip_max_list"),
             &SystemClauseType::Sleep => clause_name!("$sleep"),
             &SystemClauseType::SocketClientOpen => clause_name!("$socket_client_open"),
@@ -591,9 +616,12 @@ impl SystemClauseType {
             &SystemClauseType::GetEnv => clause_name!("$getenv"),
             &SystemClauseType::SetEnv => clause_name!("$setenv"),
             &SystemClauseType::UnsetEnv => clause_name!("$unsetenv"),
+            &SystemClauseType::Environ => clause_name!("$environ"),
+            &SystemClauseType::Shell => clause_name!("$shell"),
             &SystemClauseType::PID => clause_name!("$pid"),
             &SystemClauseType::CharsBase64 => clause_name!("$chars_base64"),
             &SystemClauseType::LoadLibraryAsStream => clause_name!("$load_library_as_stream"),
+            &SystemClauseType::LibraryPaths => clause_name!("$library_paths"),
             &SystemClauseType::DevourWhitespace => clause_name!("$devour_whitespace"),
             &SystemClauseType::IsSTOEnabled => clause_name!("$is_sto_enabled"),
             &SystemClauseType::SetSTOAsUnify => clause_name!("$set_sto_as_unify"),
@@ -632,6 +660,7 @@ impl SystemClauseType {
             ("$char_code", 2) => Some(SystemClauseType::CharCode),
             ("$char_type", 2) => Some(SystemClauseType::CharType),
             ("$chars_to_number", 2) => Some(SystemClauseType::CharsToNumber),
+            ("$clause_locations", 4) => Some(SystemClauseType::ClauseLocations),
             ("$codes_to_number", 2) => Some(SystemClauseType::CodesToNumber),
             ("$copy_term_without_attr_vars", 2) => Some(SystemClauseType::CopyTermWithoutAttrVars),
             ("$create_partial_string", 3) => Some(SystemClauseType::CreatePartialString),
@@ -682,6 +711,7 @@ impl SystemClauseType {
             ("$get_lh_from_offset", 2) => Some(SystemClauseType::GetLiftedHeapFromOffset),
             ("$get_lh_from_offset_diff", 3) => Some(SystemClauseType::GetLiftedHeapFromOffsetDiff),
             ("$get_double_quotes", 1) => Some(SystemClauseType::GetDoubleQuotes),
+            ("$get_char_conversion_flag", 1) => Some(SystemClauseType::GetCharConversionFlag),
             ("$get_scc_cleaner", 1) => Some(SystemClauseType::GetSCCCleaner),
             ("$halt", 1) => Some(SystemClauseType::Halt),
             ("$head_is_dynamic", 2) => Some(SystemClauseType::HeadIsDynamic),
@@ -691,12 +721,15 @@ impl SystemClauseType {
             ("$maybe", 0) => Some(SystemClauseType::Maybe),
             ("$cpu_now", 1) => Some(SystemClauseType::CpuNow),
             ("$current_time", 1) => Some(SystemClauseType::CurrentTime),
+            ("$get_time", 1) => Some(SystemClauseType::GetTime),
+            ("$timestamp_to_time", 2) => Some(SystemClauseType::TimestampToTime),
             ("$module_exists", 1) => Some(SystemClauseType::ModuleExists),
             ("$no_such_predicate", 2) => Some(SystemClauseType::NoSuchPredicate),
             ("$number_to_chars", 2) => Some(SystemClauseType::NumberToChars),
             ("$number_to_codes", 2) => Some(SystemClauseType::NumberToCodes),
             ("$op", 3) => Some(SystemClauseType::OpDeclaration),
-            ("$open", 7) => Some(SystemClauseType::Open),
+            ("$open", 8) => Some(SystemClauseType::Open),
+            ("$open_chars_stream", 2) => Some(SystemClauseType::OpenCharsStream),
             ("$redo_attr_var_binding", 2) => Some(SystemClauseType::RedoAttrVarBinding),
             ("$remove_call_policy_check", 1) => Some(SystemClauseType::RemoveCallPolicyCheck),
             ("$remove_inference_counter", 2) => Some(SystemClauseType::RemoveInferenceCounter),
@@ -725,9 +758,13 @@ impl SystemClauseType {
             ("$reset_block", 1) => Some(SystemClauseType::ResetBlock),
             ("$reset_cont_marker", 0) => Some(SystemClauseType::ResetContinuationMarker),
             ("$return_from_verify_attr", 0) => Some(SystemClauseType::ReturnFromVerifyAttr),
+            ("$setarg", 4) => Some(SystemClauseType::SetArg),
             ("$set_ball", 1) => Some(SystemClauseType::SetBall),
             ("$set_cp_by_default", 1) => Some(SystemClauseType::SetCutPointByDefault(temp_v!(1))),
             ("$set_double_quotes", 1) => Some(SystemClauseType::SetDoubleQuotes),
+            ("$set_char_conversion_flag", 1) => Some(SystemClauseType::SetCharConversionFlag),
+            ("$char_conversion", 2) => Some(SystemClauseType::CharConversion),
+            ("$char_conversions", 1) => Some(SystemClauseType::CharConversions),
             ("$set_seed", 1) => Some(SystemClauseType::SetSeed),
             ("$skip_max_list", 4) => Some(SystemClauseType::SkipMaxList),
             ("$sleep", 1) => Some(SystemClauseType::Sleep),
@@ -791,8 +828,8 @@ impl SystemClauseType {
             )),
             ("$variant", 2) => Some(SystemClauseType::Variant),
             ("$wam_instructions", 4) => Some(SystemClauseType::WAMInstructions),
-            ("$write_term", 7) => Some(SystemClauseType::WriteTerm),
-            ("$write_term_to_chars", 7) => Some(SystemClauseType::WriteTermToChars),
+            ("$write_term", 8) => Some(SystemClauseType::WriteTerm),
+            ("$write_term_to_chars", 8) => Some(SystemClauseType::WriteTermToChars),
             ("$scryer_prolog_version", 1) => Some(SystemClauseType::ScryerPrologVersion),
             ("$crypto_random_byte", 1) => Some(SystemClauseType::CryptoRandomByte),
             ("$crypto_data_hash", 4) => Some(SystemClauseType::CryptoDataHash),
@@ -811,9 +848,12 @@ impl SystemClauseType {
             ("$getenv", 2) => Some(SystemClauseType::GetEnv),
             ("$setenv", 2) => Some(SystemClauseType::SetEnv),
             ("$unsetenv", 1) => Some(SystemClauseType::UnsetEnv),
+            ("$environ", 1) => Some(SystemClauseType::Environ),
+            ("$shell", 2) => Some(SystemClauseType::Shell),
             ("$pid", 1) => Some(SystemClauseType::PID),
             ("$chars_base64", 4) => Some(SystemClauseType::CharsBase64),
             ("$load_library_as_stream", 3) => Some(SystemClauseType::LoadLibraryAsStream),
+            ("$library_paths", 1) => Some(SystemClauseType::LibraryPaths),
             ("$push_load_context", 2) => Some(SystemClauseType::REPL(REPLCodePtr::PushLoadContext)),
             ("$pop_load_state_payload", 1) => {
                 Some(SystemClauseType::REPL(REPLCodePtr::PopLoadStatePayload))
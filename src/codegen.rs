@@ -532,6 +532,23 @@ impl<'a, TermMarker: Allocator<'a>> CodeGenerator<TermMarker> {
                     code.push(is_atomic!(r));
                 }
             },
+            &InlinedClauseType::IsCallable(..) => match terms[0].as_ref() {
+                &Term::Clause(..)
+                | &Term::Cons(..)
+                | &Term::Constant(_, Constant::Char(_))
+                | &Term::Constant(_, Constant::EmptyList)
+                | &Term::Constant(_, Constant::Atom(..)) => {
+                    code.push(succeed!());
+                }
+                &Term::Var(ref vr, ref name) => {
+                    self.marker.reset_arg(1);
+                    let r = self.mark_non_callable(name.clone(), 1, term_loc, vr, code);
+                    code.push(is_callable!(r));
+                }
+                _ => {
+                    code.push(fail!());
+                }
+            },
             &InlinedClauseType::IsCompound(..) => match terms[0].as_ref() {
                 &Term::Clause(..) | &Term::Cons(..) => {
                     code.push(succeed!());
@@ -228,7 +228,7 @@ impl PredicateClause {
 }
 
 #[derive(Debug, Clone)]
-pub(crate) enum ModuleSource {
+pub enum ModuleSource {
     Library(ClauseName),
     File(ClauseName),
 }
@@ -276,7 +276,7 @@ pub(crate) enum Declaration {
 }
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq, Ord, PartialOrd)]
-pub(crate) struct OpDecl {
+pub struct OpDecl {
     pub(crate) prec: usize,
     pub(crate) spec: Specifier,
     pub(crate) name: ClauseName,
@@ -284,7 +284,7 @@ pub(crate) struct OpDecl {
 
 impl OpDecl {
     #[inline]
-    pub(crate) fn new(prec: usize, spec: Specifier, name: ClauseName) -> Self {
+    pub fn new(prec: usize, spec: Specifier, name: ClauseName) -> Self {
         Self { prec, spec, name }
     }
 
@@ -327,6 +327,13 @@ impl OpDecl {
         existing_desc: Option<OpDesc>,
         op_dir: &mut OpDir,
     ) -> Result<(), SessionError> {
+        self.check_fixity(existing_desc)?;
+        self.insert_into_op_dir(op_dir);
+        Ok(())
+    }
+
+    // an operator can't be both infix and postfix (ISO 6.3.4.2).
+    pub(crate) fn check_fixity(&self, existing_desc: Option<OpDesc>) -> Result<(), SessionError> {
         let (spec, name) = (self.spec, self.name.clone());
 
         if is_infix!(spec) {
@@ -345,7 +352,6 @@ impl OpDecl {
             }
         }
 
-        self.insert_into_op_dir(op_dir);
         Ok(())
     }
 }
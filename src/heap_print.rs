@@ -350,6 +350,7 @@ pub(crate) struct HCPrinter<'a, Outputter> {
     pub(crate) ignore_ops: bool,
     pub(crate) print_strings_as_strs: bool,
     pub(crate) max_depth: usize,
+    pub(crate) spacing_next_argument: bool,
 }
 
 macro_rules! push_space_if_amb {
@@ -477,6 +478,7 @@ impl<'a, Outputter: HCValueOutputter> HCPrinter<'a, Outputter> {
             var_names: IndexMap::new(),
             print_strings_as_strs: false,
             max_depth: 0,
+            spacing_next_argument: false,
         }
     }
 
@@ -904,8 +906,6 @@ impl<'a, Outputter: HCValueOutputter> HCPrinter<'a, Outputter> {
     fn print_op_addendum(&mut self, atom: &str) -> String {
         if !self.quoted || non_quoted_token(atom.chars()) {
             atom.to_string()
-        } else if atom == "''" {
-            "''".to_string()
         } else {
             let mut result = String::new();
 
@@ -1516,7 +1516,13 @@ impl<'a, Outputter: HCValueOutputter> HCPrinter<'a, Outputter> {
                     }
                     TokenOrRedirect::HeadTailSeparator => self.append_str("|"),
                     TokenOrRedirect::Number(n, op) => self.print_number(n, &op),
-                    TokenOrRedirect::Comma => self.append_str(","),
+                    TokenOrRedirect::Comma => {
+                        if self.spacing_next_argument {
+                            self.append_str(", ")
+                        } else {
+                            self.append_str(",")
+                        }
+                    }
                     TokenOrRedirect::Space => self.push_char(' '),
                     TokenOrRedirect::LeftCurly => self.push_char('{'),
                     TokenOrRedirect::RightCurly => self.push_char('}'),
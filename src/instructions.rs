@@ -366,6 +366,7 @@ pub(crate) enum ArithmeticInstruction {
     ASin(ArithmeticTerm, usize),
     ATan(ArithmeticTerm, usize),
     ATan2(ArithmeticTerm, ArithmeticTerm, usize),
+    LogBase(ArithmeticTerm, ArithmeticTerm, usize),
     Sqrt(ArithmeticTerm, usize),
     Abs(ArithmeticTerm, usize),
     Float(ArithmeticTerm, usize),
@@ -376,6 +377,8 @@ pub(crate) enum ArithmeticInstruction {
     Neg(ArithmeticTerm, usize),
     Plus(ArithmeticTerm, usize),
     BitwiseComplement(ArithmeticTerm, usize),
+    Msb(ArithmeticTerm, usize),
+    Lsb(ArithmeticTerm, usize),
 }
 
 fn arith_instr_unary_functor(
@@ -464,7 +467,10 @@ impl ArithmeticInstruction {
                 arith_instr_bin_functor(h, "rem", at_1, at_2, t)
             }
             &ArithmeticInstruction::ATan2(ref at_1, ref at_2, t) => {
-                arith_instr_bin_functor(h, "rem", at_1, at_2, t)
+                arith_instr_bin_functor(h, "atan2", at_1, at_2, t)
+            }
+            &ArithmeticInstruction::LogBase(ref at_1, ref at_2, t) => {
+                arith_instr_bin_functor(h, "log", at_1, at_2, t)
             }
             &ArithmeticInstruction::Gcd(ref at_1, ref at_2, t) => {
                 arith_instr_bin_functor(h, "gcd", at_1, at_2, t)
@@ -500,6 +506,8 @@ impl ArithmeticInstruction {
             &ArithmeticInstruction::BitwiseComplement(ref at, t) => {
                 arith_instr_unary_functor(h, "\\", at, t)
             }
+            &ArithmeticInstruction::Msb(ref at, t) => arith_instr_unary_functor(h, "msb", at, t),
+            &ArithmeticInstruction::Lsb(ref at, t) => arith_instr_unary_functor(h, "lsb", at, t),
         }
     }
 }
@@ -1,11 +1,31 @@
-:- module(arithmetic, [expmod/4, lsb/2, msb/2, number_to_rational/2,
+:- module(arithmetic, [divmod/4, expmod/4, lsb/2, msb/2,
+                       number_to_rational/2,
                        number_to_rational/3,
-                       rational_numerator_denominator/3]).
+                       rational_numerator_denominator/3,
+                       succ/2]).
 
 :- use_module(library(charsio), [write_term_to_chars/3]).
 :- use_module(library(error)).
 :- use_module(library(lists), [append/3, member/2]).
 
+divmod(Dividend, Divisor, Quotient, Remainder) :-
+    must_be(integer, Dividend),
+    must_be(integer, Divisor),
+    Quotient is Dividend div Divisor,
+    Remainder is Dividend mod Divisor.
+
+succ(X, Y) :-
+    can_be(integer, X),
+    can_be(integer, Y),
+    (   integer(X), X < 0 -> type_error(not_less_than_zero, X, succ/2)
+    ;   integer(Y), Y < 0 -> type_error(not_less_than_zero, Y, succ/2)
+    ;   integer(X) -> Y is X + 1
+    ;   integer(Y) ->
+        Y > 0,
+        X is Y - 1
+    ;   instantiation_error(succ/2)
+    ).
+
 expmod(Base, Expo, Mod, R) :-
     (   member(N, [Base, Expo, Mod]), var(N) -> instantiation_error(expmod/4)
     ;   member(N, [Base, Expo, Mod]), \+ integer(N) ->
@@ -7,13 +7,25 @@
 
 between(Lower, Upper, X) :-
     must_be(integer, Lower),
-    must_be(integer, Upper),
-    can_be(integer, X),
-    (   nonvar(X) ->
-        Lower =< X,
-        X =< Upper
-    ;   compare(Ord, Lower, Upper),
-        between_(Ord, Lower, Upper, X)
+    (   infinite(Upper) ->
+        can_be(integer, X),
+        (   nonvar(X) ->
+            Lower =< X
+        ;   enumerate_nats(Lower, X)
+        )
+    ;   must_be(integer, Upper),
+        can_be(integer, X),
+        (   nonvar(X) ->
+            Lower =< X,
+            X =< Upper
+        ;   compare(Ord, Lower, Upper),
+            between_(Ord, Lower, Upper, X)
+        )
+    ).
+
+infinite(Upper) :-
+    (   Upper == inf -> true
+    ;   Upper == infinite
     ).
 
 between_(<, Lower0, Upper, X) :-
@@ -6,14 +6,15 @@
                      atom_chars/2, atom_codes/2, atom_concat/3,
                      atom_length/2, bagof/3, call/1, call/2, call/3,
                      call/4, call/5, call/6, call/7, call/8, call/9,
-                     callable/1, catch/3, char_code/2, clause/2,
-                     close/1, close/2, current_input/1,
-                     current_output/1, current_op/3,
+                     catch/3, char_code/2, char_conversion/2, clause/2,
+                     close/1, close/2, current_input/1, cyclic_term/1,
+                     current_char_conversion/2, current_output/1,
+                     current_op/3,
                      current_predicate/1, current_prolog_flag/2,
                      fail/0, false/0, findall/3, findall/4,
                      flush_output/0, flush_output/1, get_byte/1,
                      get_byte/2, get_char/1, get_char/2, get_code/1,
-                     get_code/2, halt/0, halt/1, nl/0,
+                     get_code/2, halt/0, halt/1, is_list/1, nl/0,
                      nl/1, number_chars/2, number_codes/2, once/1,
                      op/3, open/3, open/4, peek_byte/1, peek_byte/2,
                      peek_char/1, peek_char/2, peek_code/1,
@@ -26,7 +27,8 @@
                      term_variables/2, throw/1, true/0,
                      unify_with_occurs_check/2, write/1, write/2,
                      write_canonical/1, write_canonical/2,
-                     write_term/2, write_term/3, writeq/1, writeq/2]).
+                     write_term/2, write_term/3, writeln/1, writeln/2,
+                     writeq/1, writeq/2]).
 
 
 % unify.
@@ -130,6 +132,8 @@ current_prolog_flag(Flag, Value) :- Flag == integer_rounding_function, !, Value
 current_prolog_flag(integer_rounding_function, toward_zero).
 current_prolog_flag(Flag, Value) :- Flag == double_quotes, !, '$get_double_quotes'(Value).
 current_prolog_flag(double_quotes, Value) :- '$get_double_quotes'(Value).
+current_prolog_flag(Flag, Value) :- Flag == char_conversion, !, '$get_char_conversion_flag'(Value).
+current_prolog_flag(char_conversion, Value) :- '$get_char_conversion_flag'(Value).
 current_prolog_flag(Flag, _) :- Flag == max_integer, !, '$fail'.
 current_prolog_flag(Flag, _) :- Flag == min_integer, !, '$fail'.
 current_prolog_flag(Flag, OccursCheckEnabled) :-
@@ -166,6 +170,10 @@ set_prolog_flag(double_quotes, atom) :-
     !, '$set_double_quotes'(atom). % 7.11.2.5, list of char codes (UTF8).
 set_prolog_flag(double_quotes, codes) :-
     !, '$set_double_quotes'(codes).
+set_prolog_flag(char_conversion, on) :-
+    !, '$set_char_conversion_flag'(on). % 7.11.2.1
+set_prolog_flag(char_conversion, off) :-
+    !, '$set_char_conversion_flag'(off).
 set_prolog_flag(occurs_check, true) :-
     !, '$set_sto_as_unify'.
 set_prolog_flag(occurs_check, false) :-
@@ -175,12 +183,40 @@ set_prolog_flag(occurs_check, error) :-
 set_prolog_flag(double_quotes, Value) :-
     throw(error(domain_error(flag_value, double_quotes + Value),
                 set_prolog_flag/2)). % 8.17.1.3 e
+set_prolog_flag(char_conversion, Value) :-
+    throw(error(domain_error(flag_value, char_conversion + Value),
+                set_prolog_flag/2)). % 8.17.1.3 e
 set_prolog_flag(Flag, _) :-
     atom(Flag),
     throw(error(domain_error(prolog_flag, Flag), set_prolog_flag/2)). % 8.17.1.3 d
 set_prolog_flag(Flag, _) :-
     throw(error(type_error(atom, Flag), set_prolog_flag/2)). % 8.17.1.3 c
 
+% character conversion.
+
+char_conversion(InChar, OutChar) :-
+    (  var(InChar) ; var(OutChar) ),
+    throw(error(instantiation_error, char_conversion/2)). % 8.14.5.3 a
+char_conversion(InChar, OutChar) :-
+    (  '$char_conversion_arg'(InChar),
+       '$char_conversion_arg'(OutChar)
+    -> '$char_conversion'(InChar, OutChar)
+    ;  throw(error(representation_error(character), char_conversion/2)) % 8.14.5.3 b
+    ).
+
+'$char_conversion_arg'(C) :-
+    atom(C),
+    atom_length(C, 1).
+
+current_char_conversion(InChar, OutChar) :-
+    (  nonvar(InChar), \+ '$char_conversion_arg'(InChar)
+    -> throw(error(representation_error(character), current_char_conversion/2)) % 8.14.6.3 b
+    ;  nonvar(OutChar), \+ '$char_conversion_arg'(OutChar)
+    -> throw(error(representation_error(character), current_char_conversion/2))
+    ;  '$char_conversions'(Pairs),
+       lists:member(InChar-OutChar, Pairs)
+    ).
+
 % control operators.
 
 fail :- '$fail'.
@@ -436,10 +472,23 @@ parse_options_list(Options, Selector, DefaultPairs, OptionValues, Stub) :-
 
 
 parse_write_options(Options, OptionValues, Stub) :-
-    DefaultOptions = [ignore_ops-false, max_depth-0, numbervars-false,
-                      quoted-false, variable_names-[]],
+    DefaultOptions = [cycles-true, fullstop-false, ignore_ops-false,
+                      max_depth-0, numbervars-false, quoted-false,
+                      spacing-standard, variable_names-[]],
     parse_options_list(Options, builtins:parse_write_options_, DefaultOptions, OptionValues, Stub).
 
+parse_write_options_(cycles(Cycles), cycles-Cycles) :-
+    (  nonvar(Cycles),
+       lists:member(Cycles, [true, false])
+    ;
+       throw(error(domain_error(write_option, cycles(Cycles)), _))
+    ).
+parse_write_options_(fullstop(FullStop), fullstop-FullStop) :-
+    (  nonvar(FullStop),
+       lists:member(FullStop, [true, false])
+    ;
+       throw(error(domain_error(write_option, fullstop(FullStop)), _))
+    ).
 parse_write_options_(ignore_ops(IgnoreOps), ignore_ops-IgnoreOps) :-
     (  nonvar(IgnoreOps),
        lists:member(IgnoreOps, [true, false])
@@ -452,6 +501,12 @@ parse_write_options_(quoted(Quoted), quoted-Quoted) :-
     ;
        throw(error(domain_error(write_option, quoted(Quoted)), _))
     ).
+parse_write_options_(spacing(Spacing), spacing-Spacing) :-
+    (  nonvar(Spacing),
+       lists:member(Spacing, [standard, next_argument])
+    ;
+       throw(error(domain_error(write_option, spacing(Spacing)), _))
+    ).
 parse_write_options_(numbervars(NumberVars), numbervars-NumberVars) :-
     (  nonvar(NumberVars),
        lists:member(NumberVars, [true, false])
@@ -497,30 +552,58 @@ write_term(Term, Options) :-
     write_term(Stream, Term, Options).
 
 write_term(Stream, Term, Options) :-
-    parse_write_options(Options, [IgnoreOps, MaxDepth, NumberVars, Quoted, VNNames], write_term/3),
-    '$write_term'(Stream, Term, IgnoreOps, NumberVars, Quoted, VNNames, MaxDepth).
+    parse_write_options(Options,
+                        [Cycles, FullStop, IgnoreOps, MaxDepth, NumberVars,
+                         Quoted, Spacing, VNNames],
+                        write_term/3),
+    check_write_cycles(Cycles, Term, write_term/3),
+    '$write_term'(Stream, Term, IgnoreOps, NumberVars, Quoted, VNNames, MaxDepth, Spacing),
+    (  FullStop == true ->
+       % the space keeps the full stop from fusing with a trailing
+       % symbol char of the term.
+       put_char(Stream, ' '),
+       put_char(Stream, '.')
+    ;  true
+    ).
+
+% with cycles(false), cyclic terms are rejected rather than written
+% with their cycles elided as '...'.
+check_write_cycles(Cycles, Term, Stub) :-
+    (  Cycles == false,
+       \+ acyclic_term(Term) ->
+       throw(error(type_error(acyclic_term, Term), Stub))
+    ;  true
+    ).
 
 
 write(Term) :-
     current_output(Stream),
-    '$write_term'(Stream, Term, false, true, false, [], 0).
+    '$write_term'(Stream, Term, false, true, false, [], 0, standard).
 
 write(Stream, Term) :-
-    '$write_term'(Stream, Term, false, true, false, [], 0).
+    '$write_term'(Stream, Term, false, true, false, [], 0, standard).
+
+writeln(Term) :-
+    current_output(Stream),
+    writeln(Stream, Term).
+
+writeln(Stream, Term) :-
+    write(Stream, Term),
+    nl(Stream).
 
 write_canonical(Term) :-
     current_output(Stream),
-    '$write_term'(Stream, Term, true, false, true, [], 0).
+    '$write_term'(Stream, Term, true, false, true, [], 0, standard).
 
 write_canonical(Stream, Term) :-
-    '$write_term'(Stream, Term, true, false, true, [], 0).
+    '$write_term'(Stream, Term, true, false, true, [], 0, standard).
 
 writeq(Term) :-
     current_output(Stream),
-    '$write_term'(Stream, Term, false, true, true, [], 0).
+    '$write_term'(Stream, Term, false, true, true, [], 0, standard).
 
 writeq(Stream, Term) :-
-    '$write_term'(Stream, Term, false, true, true, [], 0).
+    '$write_term'(Stream, Term, false, true, true, [], 0, standard).
 
 select_rightmost_options([Option-Value | OptionPairs], OptionValues) :-
     (  pairs:same_key(Option, OptionPairs, OtherValues, _),
@@ -534,10 +617,18 @@ select_rightmost_options([], []).
 
 
 parse_read_term_options(Options, OptionValues, Stub) :-
-    DefaultOptions = [singletons-_, variables-_, variable_names-_],
+    DefaultOptions = [double_quotes-_, singletons-_, variables-_, variable_names-_],
     parse_options_list(Options, builtins:parse_read_term_options_, DefaultOptions, OptionValues, Stub).
 
 
+parse_read_term_options_(double_quotes(DQ), double_quotes-DQ) :-
+    (  var(DQ) ->
+       throw(error(instantiation_error, _))
+    ;  lists:member(DQ, [atom, chars, codes]) ->
+       true
+    ;
+       throw(error(domain_error(read_option, double_quotes(DQ)), _))
+    ).
 parse_read_term_options_(singletons(Vars), singletons-Vars).
 parse_read_term_options_(variables(Vars), variables-Vars).
 parse_read_term_options_(variable_names(Vars), variable_names-Vars).
@@ -547,8 +638,25 @@ parse_read_term_options_(E,_) :-
 
 
 read_term(Stream, Term, Options) :-
-    parse_read_term_options(Options, [Singletons, VariableNames, Variables], read_term/3),
-    '$read_term'(Stream, Term, Singletons, Variables, VariableNames).
+    parse_read_term_options(Options, [DoubleQuotes, Singletons, VariableNames, Variables], read_term/3),
+    (  var(DoubleQuotes) ->
+       '$read_term'(Stream, Term, Singletons, Variables, VariableNames)
+    ;  % the double_quotes option only applies to this read, so the
+       % flag is restored whether '$read_term' succeeds, fails or throws.
+       '$get_double_quotes'(DoubleQuotes0),
+       '$set_double_quotes'(DoubleQuotes),
+       (  catch('$read_term'(Stream, Term, Singletons, Variables, VariableNames),
+                E,
+                true) ->
+          true
+       ;  Failed = true
+       ),
+       '$set_double_quotes'(DoubleQuotes0),
+       (  nonvar(E) -> throw(E)
+       ;  Failed == true -> false
+       ;  true
+       )
+    ).
 
 read_term(Term, Options) :-
     current_input(Stream),
@@ -606,6 +714,19 @@ end_block(Bb, NBb) :-
     '$fail'.
 
 :- non_counted_backtracking handle_ball/3.
+%% The recovery goal of an aborted query is run, but the abort goes
+%% on.
+handle_ball('$aborted', C, R) :-
+    !,
+    (  C = '$aborted' ->
+       '$erase_ball',
+       (  catch(R, _, true) ->
+          true
+       ;  true
+       ),
+       throw('$aborted')
+    ;  '$unwind_stack'
+    ).
 handle_ball(C, C, R) :-
     !,
     '$erase_ball',
@@ -812,8 +933,9 @@ clause(H, B) :-
     ;  throw(error(type_error(callable, H), clause/2))
     ).
 
-call_asserta(Head, Body, Name, Arity, Module) :-
-    '$clause_body_is_valid'(Body),
+call_asserta(Head, Body0, Name, Arity, Module) :-
+    '$clause_body_is_valid'(Body0),
+    loader:expand_asserted_body(Body0, Module, Body),
     functor(_, Name, Arity),
     '$asserta'(Head, Body, Name, Arity, Module).
 
@@ -878,8 +1000,9 @@ module_assertz_clause(Head, Body, Module) :-
     ).
 
 
-call_assertz(Head, Body, Name, Arity, Module) :-
-    '$clause_body_is_valid'(Body),
+call_assertz(Head, Body0, Name, Arity, Module) :-
+    '$clause_body_is_valid'(Body0),
+    loader:expand_asserted_body(Body0, Module, Body),
     functor(_, Name, Arity),
     '$assertz'(Head, Body, Name, Arity, Module).
 
@@ -919,23 +1042,11 @@ assertz(Clause) :-
     ).
 
 
-module_retract_clauses([Clause|Clauses0], Head, Body, Name, Arity, Module) :-
-    functor(VarHead, Name, Arity),
-    findall((VarHead :- VarBody), Module:'$clause'(VarHead, VarBody), Clauses1),
-    (  first_match_index(Clauses1, (Head :- Body), 0, N) ->
-       '$retract_clause'(Name, Arity, N, Module)
-    ;  Clause = (Head :- Body)
-    ),
-    (  Clauses0 == [] -> !
-    ;  true
-    ).
-
-module_retract_clauses([_|Clauses0], Head, Body, Name, Arity, Module) :-
-    module_retract_clauses(Clauses0, Head, Body, Name, Arity, Module).
-
 call_module_retract(Head, Body, Name, Arity, Module) :-
-    findall((Head :- Body), Module:'$clause'(Head, Body), Clauses),
-    module_retract_clauses(Clauses, Head, Body, Name, Arity, Module).
+    functor(VarHead, Name, Arity),
+    findall((VarHead :- VarBody), Module:'$clause'(VarHead, VarBody), Clauses),
+    '$clause_locations'(Name, Arity, Module, Locs),
+    retract_clauses(Locs, Clauses, Head, Body, Name, Arity, Module).
 
 retract_module_clause(Head, Body, Module) :-
     (  var(Head) ->
@@ -954,29 +1065,34 @@ retract_module_clause(Head, Body, Module) :-
     ).
 
 
-first_match_index([Clause | Clauses], Clause, N, N) :-
-    !.
-first_match_index([_ | Clauses], Clause, N0, N) :-
-    N1 is N0 + 1,
-    first_match_index(Clauses, Clause, N1, N).
+% retract/1 offers the clauses present when it was called, in order.
+% Each is known by the location of its code, so that a clause removed
+% since then is skipped on backtracking rather than offered again,
+% even if a variant of it has been asserted in the meantime.
 
-retract_clauses([Clause | Clauses0], Head, Body, Name, Arity) :-
-    functor(VarHead, Name, Arity),
-    findall((VarHead :- VarBody), builtins:'$clause'(VarHead, VarBody), Clauses1),
-    (  first_match_index(Clauses1, (Head :- Body), 0, N) ->
-       '$retract_clause'(Name, Arity, N, user)
-    ;  Clause = (Head :- Body)
-    ),
-    (  Clauses0 == [] -> !
+retract_clauses([Loc | Locs], [Clause | Clauses], Head, Body, Name, Arity, Module) :-
+    Clause = (Head :- Body),
+    '$clause_locations'(Name, Arity, Module, CurrentLocs),
+    clause_location_index(CurrentLocs, Loc, 0, N),
+    '$retract_clause'(Name, Arity, N, Module),
+    (  Clauses == [] -> !
     ;  true
     ).
+retract_clauses([_ | Locs], [_ | Clauses], Head, Body, Name, Arity, Module) :-
+    retract_clauses(Locs, Clauses, Head, Body, Name, Arity, Module).
 
-retract_clauses([_ | Clauses0], Head, Body, Name, Arity) :-
-    retract_clauses(Clauses0, Head, Body, Name, Arity).
+clause_location_index([Loc0 | Locs], Loc, N0, N) :-
+    (  Loc0 == Loc ->
+       N = N0
+    ;  N1 is N0 + 1,
+       clause_location_index(Locs, Loc, N1, N)
+    ).
 
 call_retract(Head, Body, Name, Arity) :-
-    findall((Head :- Body), builtins:'$clause'(Head, Body), Clauses),
-    retract_clauses(Clauses, Head, Body, Name, Arity).
+    functor(VarHead, Name, Arity),
+    findall((VarHead :- VarBody), builtins:'$clause'(VarHead, VarBody), Clauses),
+    '$clause_locations'(Name, Arity, user, Locs),
+    retract_clauses(Locs, Clauses, Head, Body, Name, Arity, user).
 
 retract_clause(Head, Body) :-
     (  var(Head) ->
@@ -1008,6 +1124,39 @@ retract(Clause) :-
     ).
 
 
+'$reset_dynamic_db' :-
+    '$get_next_db_ref'(Ref, _),
+    '$iterate_db_refs'(Ref, Name/Arity),
+    functor(Head, Name, Arity),
+    '$head_is_dynamic'(user, Head),
+    retract_clause(Head, _),
+    false.
+'$reset_dynamic_db'.
+
+'$run_directive'(Chars, Ball) :-
+    catch(( '$read_term_from_chars'(Chars, Goal),
+            (  user:Goal -> true
+            ;  Ball = failed(Goal)
+            )
+          ),
+          Ball,
+          true).
+
+'$consult_chunk'(Stream, Ball) :-
+    catch(loader:load(Stream), Ball, true).
+
+%% Result is bound to true for each answer of the query and to
+%% ball(Ball) if it throws Ball. It is left unbound once the answers
+%% run out.
+'$run_query'(Stream, VarNames, Result) :-
+    catch(( read_term(Stream, Goal, [variable_names(VarNames)]),
+            user:Goal,
+            Result = true
+          ),
+          Ball,
+          Result = ball(Ball)).
+'$run_query'(_, _, _).
+
 :- meta_predicate retractall(0).
 
 retractall(Head) :-
@@ -1281,15 +1430,54 @@ sub_atom(Atom, Before, Length, After, Sub_atom) :-
        throw(error(domain_error(not_less_than_zero, Length), sub_atom/5))
     ;  integer(After), After < 0 ->
        throw(error(domain_error(not_less_than_zero, After), sub_atom/5))
-    ;  atom_chars(Atom, AtomChars),
-       lists:append(BeforeChars, LengthAndAfterChars, AtomChars),
-       lists:append(LengthChars, AfterChars, LengthAndAfterChars),
-       '$skip_max_list'(Before, -1, BeforeChars, []),
-       '$skip_max_list'(Length, -1, LengthChars, []),
-       '$skip_max_list'(After, -1, AfterChars, []),
+    ;  atom_length(Atom, N),
+       (  atom(Sub_atom) ->
+          atom_length(Sub_atom, Length)
+       ;  true
+       ),
+       sub_atom_indices(N, Before, Length, After),
+       atom_chars(Atom, AtomChars),
+       lists:length(BeforeChars, Before),
+       lists:append(BeforeChars, Rest, AtomChars),
+       lists:length(LengthChars, Length),
+       lists:append(LengthChars, _, Rest),
        atom_chars(Sub_atom, LengthChars)
     ).
 
+% any two of Before, Length and After determine the third, so only
+% the unbound ones are enumerated.
+sub_atom_indices(N, Before, Length, After) :-
+    (  integer(Before) ->
+       (  integer(Length) -> true
+       ;  integer(After) -> Length is N - Before - After
+       ;  Max is N - Before,
+          sub_atom_between(0, Max, Length)
+       )
+    ;  integer(Length) ->
+       (  integer(After) -> Before is N - Length - After
+       ;  Max is N - Length,
+          sub_atom_between(0, Max, Before)
+       )
+    ;  integer(After) ->
+       Max is N - After,
+       sub_atom_between(0, Max, Before),
+       Length is Max - Before
+    ;  sub_atom_between(0, N, Before),
+       Max is N - Before,
+       sub_atom_between(0, Max, Length)
+    ),
+    Before >= 0,
+    Length >= 0,
+    After is N - Before - Length,
+    After >= 0.
+
+sub_atom_between(Lower, Upper, Lower) :-
+    Lower =< Upper.
+sub_atom_between(Lower, Upper, X) :-
+    Lower < Upper,
+    Lower1 is Lower + 1,
+    sub_atom_between(Lower1, Upper, X).
+
 char_code(Char, Code) :-
     (  var(Char) ->
        (  var(Code) ->
@@ -1424,7 +1612,7 @@ set_output(S) :-
 
 
 parse_stream_options(Options, OptionValues, Stub) :-
-    DefaultOptions = [alias-[], eof_action-eof_code, reposition-false, type-text],
+    DefaultOptions = [alias-[], encoding-utf8, eof_action-eof_code, reposition-false, type-text],
     parse_options_list(Options, builtins:parse_stream_options_, DefaultOptions, OptionValues, Stub).
 
 
@@ -1448,6 +1636,11 @@ parse_stream_options_(eof_action(Action), eof_action-Action) :-
     ;
        throw(error(domain_error(stream_option, eof_action(Action)), _))
     ).
+parse_stream_options_(encoding(Encoding), encoding-Encoding) :-
+    (  nonvar(Encoding), lists:member(Encoding, [utf8, octet]), !, true
+    ;
+       throw(error(domain_error(stream_option, encoding(Encoding)), _))
+    ).
 parse_stream_options_(E, _) :-
     throw(error(domain_error(stream_option, E), _)). % 8.11.5.3i)
 
@@ -1465,8 +1658,14 @@ open(SourceSink, Mode, Stream, StreamOptions) :-
     ;  nonvar(Stream) ->
        throw(error(uninstantiation_error(Stream), open/4)) % 8.11.5.3f)
     ;
-       parse_stream_options(StreamOptions, [Alias, EOFAction, Reposition, Type], open/4),
-       '$open'(SourceSink, Mode, Stream, Alias, EOFAction, Reposition, Type)
+       parse_stream_options(StreamOptions,
+                            [Alias, Encoding, EOFAction, Reposition, Type],
+                            open/4),
+       (  Encoding == octet, Mode \== read ->
+          throw(error(domain_error(stream_option, encoding(Encoding)), open/4)) % octet streams are input only.
+       ;  true
+       ),
+       '$open'(SourceSink, Mode, Stream, Alias, EOFAction, Reposition, Type, Encoding)
     ).
 
 
@@ -1636,10 +1835,29 @@ set_stream_position(S_or_a, Position) :-
     ;  throw(error(domain_error(stream_position, Position), set_stream_position/2))
     ).
 
-callable(X) :-
-    (  nonvar(X), functor(X, F, _), atom(F) ->
-       true
-    ;  false
+is_list(List) :-
+    '$skip_max_list'(_, -1, List, Tail),
+    Tail == [].
+
+cyclic_term(X) :-
+    \+ acyclic_term(X).
+
+% arg/3 jumps here when N is unbound and Term is compound, to
+% enumerate the argument positions of Term in ascending order. The
+% last position is tried without leaving a choicepoint.
+'$enumerate_arg'(N, Term, Arg) :-
+    functor(Term, _, Arity),
+    enumerate_arg_(1, Arity, N, Term, Arg).
+
+enumerate_arg_(N0, Arity, N, Term, Arg) :-
+    (  N0 < Arity ->
+       (  N = N0,
+          arg(N0, Term, Arg)
+       ;  N1 is N0 + 1,
+          enumerate_arg_(N1, Arity, N, Term, Arg)
+       )
+    ;  N = N0,
+       arg(N0, Term, Arg)
     ).
 
 nl :-
@@ -2,7 +2,11 @@
                     chars_utf8bytes/2,
                     get_single_char/1,
                     read_line_to_chars/3,
+                    read_line_to_codes/2,
+                    read_line_to_string/2,
+                    read_term_from_atom/3,
                     read_term_from_chars/2,
+                    term_to_atom/2,
                     write_term_to_chars/3,
                     chars_base64/3]).
 
@@ -65,7 +69,7 @@ extend_var_list_([V|Vs], N, VarList, NewVarList, VarType) :-
 
 char_type(Char, Type) :-
     (   var(Char) -> instantiation_error(char_type/2)
-    ;   atom_length(Char, 1) ->
+    ;   atom(Char), atom_length(Char, 1) ->
         (   ground(Type) ->
             (   ctype(Type) ->
                 '$char_type'(Char, Type)
@@ -74,7 +78,7 @@ char_type(Char, Type) :-
         ;   ctype(Type),
             '$char_type'(Char, Type)
         )
-    ;   type_error(in_character, Char, char_type/2)
+    ;   type_error(character, Char, char_type/2)
     ).
 
 
@@ -88,6 +92,7 @@ ctype(ascii_punctuation).
 ctype(binary_digit).
 ctype(control).
 ctype(decimal_digit).
+ctype(digit(_)).
 ctype(exponent).
 ctype(graphic).
 ctype(graphic_token).
@@ -101,9 +106,13 @@ ctype(octet).
 ctype(prolog).
 ctype(sign).
 ctype(solo).
+ctype(space).
 ctype(symbolic_control).
 ctype(symbolic_hexadecimal).
+ctype(to_lower(_)).
+ctype(to_upper(_)).
 ctype(upper).
+ctype(white).
 ctype(whitespace).
 
 
@@ -133,8 +142,10 @@ write_term_to_chars(_, Options, _) :-
     var(Options), instantiation_error(write_term_to_chars/3).
 write_term_to_chars(Term, Options, Chars) :-
     builtins:parse_write_options(Options,
-                                 [IgnoreOps, MaxDepth, NumberVars, Quoted, VNNames],
+                                 [Cycles, FullStop, IgnoreOps, MaxDepth, NumberVars,
+                                  Quoted, Spacing, VNNames],
                                  write_term_to_chars/3),
+    builtins:check_write_cycles(Cycles, Term, write_term_to_chars/3),
     (  nonvar(Chars)  ->
        throw(error(uninstantiation_error(Chars), write_term_to_chars/3))
     ;
@@ -142,7 +153,36 @@ write_term_to_chars(Term, Options, Chars) :-
     ),
     term_variables(Term, Vars),
     extend_var_list(Vars, VNNames, NewVarNames, numbervars),
-    '$write_term_to_chars'(Chars, Term, IgnoreOps, NumberVars, Quoted, NewVarNames, MaxDepth).
+    '$write_term_to_chars'(Chars0, Term, IgnoreOps, NumberVars, Quoted, NewVarNames,
+                           MaxDepth, Spacing),
+    (  FullStop == true ->
+       append(Chars0, [' ', '.'], Chars)
+    ;  Chars = Chars0
+    ).
+
+read_term_from_atom(Atom, Term, Options) :-
+    must_be(atom, Atom),
+    atom_chars(Atom, Chars0),
+    append(Chars0, [' ', '.'], Chars),
+    setup_call_cleanup('$open_chars_stream'(Chars, Stream),
+                       catch(read_term(Stream, Term, Options),
+                             error(syntax_error(E), _),
+                             throw(error(syntax_error(E), read_term_from_atom/3))),
+                       close(Stream)).
+
+
+term_to_atom(Term, Atom) :-
+    (  nonvar(Atom) ->
+       must_be(atom, Atom),
+       atom_chars(Atom, Chars0),
+       append(Chars0, [' ', '.'], Chars),
+       read_term_from_chars(Chars, Term0),
+       Term = Term0
+    ;  nonvar(Term) ->
+       write_term_to_chars(Term, [quoted(true)], Chars),
+       atom_chars(Atom, Chars)
+    ;  instantiation_error(term_to_atom/2)
+    ).
 
 % Encodes Ch character to list of Bytes.
 char_utf8bytes(Ch, Bytes) :-
@@ -199,6 +239,36 @@ read_line_to_chars(Stream, Cs0, Cs) :-
             )
         ).
 
+/* - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -
+   read_line_to_string(Stream, Cs) reads the next line of Stream as a
+   list of characters Cs, without the line terminator ("\n" or "\r\n").
+   Cs is end_of_file if Stream is at its end.
+
+   read_line_to_codes(Stream, Codes) is the same for codes, yielding
+   -1 if Stream is at its end.
+- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - */
+
+read_line_to_string(Stream, String) :-
+        read_line_to_chars(Stream, Cs0, []),
+        (   Cs0 == [] -> String = end_of_file
+        ;   without_line_terminator(Cs0, Cs),
+            String = Cs
+        ).
+
+read_line_to_codes(Stream, Codes) :-
+        read_line_to_string(Stream, String),
+        (   String == end_of_file -> Codes = -1
+        ;   maplist(char_code, String, Codes)
+        ).
+
+without_line_terminator(Cs0, Cs) :-
+        (   append(Cs1, ['\n'], Cs0) ->
+            (   append(Cs, ['\r'], Cs1) -> true
+            ;   Cs = Cs1
+            )
+        ;   Cs = Cs0
+        ).
+
 /* - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -
    Relation between a list of characters Cs and its Base64 encoding Bs,
    also a list of characters.
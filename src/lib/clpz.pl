@@ -5914,8 +5914,6 @@ difference_arcs([V|Vs], FL0) -->
         ;   difference_arcs(Vs, FL0)
         ).
 
-writeln(T) :- write(T), nl.
-
 :- meta_predicate must_succeed(0).
 
 must_succeed(G) :-
@@ -7087,10 +7085,6 @@ contribution_at(T, Task, Offset-Bs, Contribution) :-
             ?(Contribution) #= B*C
         ).
 
-nth1(I, Es, E) :-
-        I0 is I-1,
-        nth0(I0, Es, E).
-
 %%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%%
 
 %% disjoint2(+Rectangles)
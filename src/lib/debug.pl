@@ -6,10 +6,17 @@
     op(950, fy, *),
     (*)/1,
     ($)/1,
-    ($-)/1
+    ($-)/1,
+    leash/1,
+    nospy/1,
+    notrace/0,
+    spy/1,
+    trace/0
 ]).
 
 :- use_module(library(format), [portray_clause/1]).
+:- use_module(library(iso_ext), [bb_get/2, bb_put/2]).
+:- use_module(library(lists), [member/2, memberchk/2]).
 
 :- meta_predicate *(0).
 :- meta_predicate $(0).
@@ -24,3 +31,167 @@ $(G_0) :-
    portray_clause(exit:G_0).
 
 *(_).
+
+
+%% A four port (call, exit, redo, fail) tracer. Once trace/0 has
+%% been called, toplevel queries are run by '$trace_goal'/1, which
+%% writes each port of each goal to the current output. Only the
+%% clauses of dynamic predicates are traced goal by goal; static
+%% predicates, those of consulted files included, are traced as a
+%% single goal, so a predicate must be declared dynamic to be traced
+%% into. If spy/1 has set any spy points, only the ports of spied
+%% predicates are written, and leash/1 limits which ports are written
+%% at all.
+%%
+%% If user:'$trace_hook'(Port, Goal) is defined and succeeds, it
+%% replaces the writing of the port, so that tracing can be customised
+%% without terminal interaction.
+
+:- dynamic(spy_point/1).
+
+trace :-
+    bb_put('$tracing', true).
+
+notrace :-
+    bb_put('$tracing', false).
+
+spy(PI) :-
+    (  var(PI) ->
+       throw(error(instantiation_error, spy/1))
+    ;  PI = Name/Arity, atom(Name), integer(Arity) ->
+       (  spy_point(PI) ->
+          true
+       ;  assertz(spy_point(PI))
+       )
+    ;  throw(error(type_error(predicate_indicator, PI), spy/1))
+    ).
+
+nospy(PI) :-
+    retractall(spy_point(PI)).
+
+leash(Ports) :-
+    (  var(Ports) ->
+       throw(error(instantiation_error, leash/1))
+    ;  '$skip_max_list'(_, -1, Ports, Tail),
+       Tail == [] ->
+       (  member(Port, Ports),
+          (  var(Port) ->
+             throw(error(instantiation_error, leash/1))
+          ;  \+ memberchk(Port, [call, exit, redo, fail]) ->
+             throw(error(domain_error(port, Port), leash/1))
+          ;  false
+          )
+       ;  bb_put('$leashed_ports', Ports)
+       )
+    ;  throw(error(type_error(list, Ports), leash/1))
+    ).
+
+'$trace_goal'(G) :-
+    '$get_b_value'(Cp),
+    trace_goal(G, user, Cp).
+
+%% Cp is the choice point a cut in G cuts back to, that of the clause
+%% or query G is part of.
+trace_goal(G, _, _) :-
+    var(G),
+    !,
+    throw(error(instantiation_error, trace/0)).
+trace_goal(M:G, _, Cp) :-
+    !,
+    trace_goal(G, M, Cp).
+trace_goal(true, _, _) :-
+    !.
+trace_goal(!, _, Cp) :-
+    !,
+    '$set_cp'(Cp).
+trace_goal(notrace, _, _) :-
+    !,
+    notrace.
+trace_goal(call(G), M, _) :-
+    !,
+    opaque_trace_goal(G, M).
+trace_goal(once(G), M, _) :-
+    !,
+    once(opaque_trace_goal(G, M)).
+trace_goal((A, B), M, Cp) :-
+    !,
+    trace_goal(A, M, Cp),
+    trace_goal(B, M, Cp).
+trace_goal((C -> T ; E), M, Cp) :-
+    !,
+    (  opaque_trace_goal(C, M) ->
+       trace_goal(T, M, Cp)
+    ;  trace_goal(E, M, Cp)
+    ).
+trace_goal((C -> T), M, Cp) :-
+    !,
+    (  opaque_trace_goal(C, M) ->
+       trace_goal(T, M, Cp)
+    ).
+trace_goal((A ; B), M, Cp) :-
+    !,
+    (  trace_goal(A, M, Cp)
+    ;  trace_goal(B, M, Cp)
+    ).
+trace_goal(\+ G, M, _) :-
+    !,
+    \+ opaque_trace_goal(G, M).
+trace_goal(G, M, _) :-
+    (  port(call, G, M)
+    ;  port(fail, G, M),
+       false
+    ),
+    trace_predicate(G, M),
+    (  port(exit, G, M)
+    ;  port(redo, G, M),
+       false
+    ).
+
+%% Traces G as a goal a cut cannot escape, like the condition of an
+%% if-then-else.
+opaque_trace_goal(G, M) :-
+    '$get_b_value'(Cp),
+    trace_goal(G, M, Cp).
+
+%% Only the clauses of dynamic predicates can be read back by clause/2,
+%% so static predicates are called untraced.
+trace_predicate(G, M) :-
+    (  callable(G),
+       '$head_is_dynamic'(M, G) ->
+       '$get_b_value'(Cp),
+       clause(M:G, Body),
+       trace_goal(Body, M, Cp)
+    ;  call(M:G)
+    ).
+
+port(Port, G, M) :-
+    (  traced_goal(G),
+       leashed_port(Port) ->
+       (  M == user ->
+          QG = G
+       ;  QG = M:G
+       ),
+       (  catch(user:'$trace_hook'(Port, QG),
+                error(existence_error(procedure, '$trace_hook'/2), _),
+                false) ->
+          true
+       ;  write(Port),
+          write(': '),
+          writeq(QG),
+          nl
+       )
+    ;  true
+    ).
+
+leashed_port(Port) :-
+    (  bb_get('$leashed_ports', Ports) ->
+       memberchk(Port, Ports)
+    ;  true
+    ).
+
+traced_goal(G) :-
+    (  spy_point(_) ->
+       functor(G, Name, Arity),
+       spy_point(Name/Arity)
+    ;  true
+    ).
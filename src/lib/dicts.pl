@@ -0,0 +1,82 @@
+/* - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -
+   Lightweight dictionaries, represented as dict(Tag, Pairs), where
+   Pairs is a list of Key-Value pairs sorted by Key and without
+   duplicate keys.
+
+   Example:
+
+       ?- dict_pairs(D0, point, [y-2,x-1]),
+          put_dict(x, D0, 3, D),
+          get_dict(x, D, X).
+          D0 = dict(point,[x-1,y-2]), D = dict(point,[x-3,y-2]), X = 3.
+- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - */
+
+:- module(dicts, [dict_pairs/3,
+                  get_dict/3,
+                  put_dict/4]).
+
+:- use_module(library(error)).
+:- use_module(library(lists)).
+
+dict_pairs(Dict, Tag, Pairs) :-
+        (   nonvar(Dict) ->
+            must_be_dict(Dict),
+            Dict = dict(Tag, Pairs)
+        ;   must_be(list, Pairs),
+            keysort(Pairs, Pairs1),
+            (   unique_keys(Pairs1) -> true
+            ;   domain_error(unique_key_pairs, Pairs, dict_pairs/3)
+            ),
+            Dict = dict(Tag, Pairs1)
+        ).
+
+unique_keys([]).
+unique_keys([K-_|Ps]) :-
+        unique_keys(Ps, K).
+
+unique_keys([], _).
+unique_keys([K-_|Ps], K0) :-
+        K0 \== K,
+        unique_keys(Ps, K).
+
+get_dict(Key, Dict, Value) :-
+        must_be_dict(Dict),
+        Dict = dict(_, Pairs),
+        (   var(Key) ->
+            member(Key-Value, Pairs)
+        ;   get_pair(Pairs, Key, Value)
+        ).
+
+get_pair([K-V|Ps], Key, Value) :-
+        compare(Order, Key, K),
+        get_pair_(Order, Ps, Key, Value, V).
+
+get_pair_(=, _, _, Value, Value).
+get_pair_(>, Ps, Key, Value, _) :-
+        get_pair(Ps, Key, Value).
+
+put_dict(Key, Dict0, Value, dict(Tag, Pairs)) :-
+        must_be_dict(Dict0),
+        Dict0 = dict(Tag, Pairs0),
+        (   var(Key) ->
+            instantiation_error(put_dict/4)
+        ;   put_pair(Pairs0, Key, Value, Pairs)
+        ).
+
+put_pair([], Key, Value, [Key-Value]).
+put_pair([K-V|Ps0], Key, Value, Ps) :-
+        compare(Order, Key, K),
+        put_pair_(Order, K, V, Ps0, Key, Value, Ps).
+
+put_pair_(<, K, V, Ps, Key, Value, [Key-Value,K-V|Ps]).
+put_pair_(=, _, _, Ps, Key, Value, [Key-Value|Ps]).
+put_pair_(>, K, V, Ps0, Key, Value, [K-V|Ps]) :-
+        put_pair(Ps0, Key, Value, Ps).
+
+must_be_dict(Dict) :-
+        (   var(Dict) ->
+            instantiation_error(must_be_dict/1)
+        ;   Dict = dict(_, Pairs), is_list(Pairs) ->
+            true
+        ;   type_error(dict, Dict, must_be_dict/1)
+        ).
@@ -1,5 +1,6 @@
 :- module(error, [must_be/2,
                   can_be/2,
+                  is_of_type/2,
                   instantiation_error/1,
                   domain_error/3,
                   type_error/3
@@ -27,8 +28,12 @@
 
        - integer
        - atom
+       - callable
+       - character
        - list
        - boolean
+       - positive_integer
+       - nonneg
 - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - */
 
 must_be(Type, Term) :-
@@ -48,6 +53,17 @@ must_be_(character, T)  :- check_(error:character, character, T).
 must_be_(list, Term)    :- check_(error:ilist, list, Term).
 must_be_(type, Term)    :- check_(error:type, type, Term).
 must_be_(boolean, Term) :- check_(error:boolean, boolean, Term).
+must_be_(callable, Term) :- check_(callable, callable, Term).
+must_be_(positive_integer, Term) :-
+        check_(integer, integer, Term),
+        (   Term >= 1 -> true
+        ;   domain_error(positive_integer, Term, must_be/2)
+        ).
+must_be_(nonneg, Term) :-
+        check_(integer, integer, Term),
+        (   Term >= 0 -> true
+        ;   domain_error(not_less_than_zero, Term, must_be/2)
+        ).
 
 check_(Pred, Type, Term) :-
         (   var(Term) -> instantiation_error(must_be/2)
@@ -72,6 +88,9 @@ type(character).
 type(list).
 type(var).
 type(boolean).
+type(callable).
+type(positive_integer).
+type(nonneg).
 
 /* - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -
    can_be(Type, Term)
@@ -99,12 +118,41 @@ can_(atom, Term)    :- atom(Term).
 can_(character, T)  :- character(T).
 can_(list, Term)    :- list_or_partial_list(Term).
 can_(boolean, Term) :- boolean(Term).
+can_(callable, Term) :- callable(Term).
+can_(positive_integer, Term) :- integer(Term), Term >= 1.
+can_(nonneg, Term) :- integer(Term), Term >= 0.
 
 list_or_partial_list(Var) :- var(Var).
 list_or_partial_list([]).
 list_or_partial_list([_|Ls]) :-
         list_or_partial_list(Ls).
 
+/* - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -
+   is_of_type(Type, Term)
+
+   This predicate is intended for guards.
+
+   It succeeds iff Term is an instance of Type, and fails otherwise.
+   It never binds Term and never throws for any instantiation of Term.
+
+   It supports the same types as must_be/2.
+- - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - */
+
+is_of_type(Type, Term) :-
+        must_be(type, Type),
+        is_(Type, Term).
+
+is_(var, Term)              :- var(Term).
+is_(integer, Term)          :- integer(Term).
+is_(atom, Term)             :- atom(Term).
+is_(character, Term)        :- character(Term).
+is_(list, Term)             :- is_list(Term).
+is_(type, Term)             :- nonvar(Term), type(Term).
+is_(boolean, Term)          :- boolean(Term).
+is_(callable, Term)         :- callable(Term).
+is_(positive_integer, Term) :- integer(Term), Term >= 1.
+is_(nonneg, Term)           :- integer(Term), Term >= 0.
+
 /* - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -
    Shorthands for throwing ISO errors.
 - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - */
@@ -13,14 +13,25 @@
                     partial_string/3,
                     partial_string_tail/2,
                     setup_call_cleanup/3,
+                    string_chars/2,
+                    string_codes/2,
                     call_nth/2,
                     variant/2,
-                    copy_term_nat/2]).
+                    copy_term_nat/2,
+                    setarg/3,
+                    nb_setarg/3,
+                    recorda/3,
+                    recordz/3,
+                    recorded/3,
+                    erase/1,
+                    memo/2]).
 
 :- use_module(library(error), [can_be/2,
                                domain_error/3,
                                instantiation_error/1,
+                               must_be/2,
                                type_error/3]).
+:- use_module(library(lists), [maplist/3]).
 
 
 :- meta_predicate call_cleanup(0, 0).
@@ -29,9 +40,41 @@
 
 :- meta_predicate forall(0, 0).
 
+:- meta_predicate memo(?, 0).
+
 forall(Generate, Test) :-
     \+ (Generate, \+ Test).
 
+%% destructive assignment to the arguments of compound terms.
+
+setarg(N, Term, Value) :-
+    setarg_index(N, Term, setarg/3),
+    '$setarg'(N, Term, Value, true).
+
+%% the value is copied, so that bindings undone on backtracking can't
+%% change the assigned argument.
+
+nb_setarg(N, Term, Value) :-
+    setarg_index(N, Term, nb_setarg/3),
+    copy_term(Value, Copy),
+    '$setarg'(N, Term, Copy, false).
+
+%% fails if N isn't an argument position of Term.
+
+setarg_index(N, Term, PI) :-
+    (  var(N) ->
+       instantiation_error(PI)
+    ;  \+ integer(N) ->
+       type_error(integer, N, PI)
+    ;  var(Term) ->
+       instantiation_error(PI)
+    ;  \+ compound(Term) ->
+       type_error(compound, Term, PI)
+    ;  functor(Term, _, Arity),
+       1 =< N,
+       N =< Arity
+    ).
+
 %% (non-)backtrackable global variables.
 
 bb_put(Key, Value) :-
@@ -180,6 +223,78 @@ partial_string_tail(String, Tail) :-
     ;  throw(error(type_error(partial_string, String), partial_string_tail/2))
     ).
 
+%% strings are lists of characters, so string_chars/2 relates a string
+%% to itself. a string read under double_quotes set to atom or codes
+%% is converted from the atom or list of codes. a list of characters
+%% given for String is stored as a partial string.
+
+string_chars(String, Chars) :-
+    (  nonvar(String) ->
+       string_to_chars(String, Chars0, string_chars/2),
+       Chars = Chars0
+    ;  nonvar(Chars) ->
+       must_be_string(Chars, string_chars/2),
+       chars_to_string(Chars, String)
+    ;  instantiation_error(string_chars/2)
+    ).
+
+string_codes(String, Codes) :-
+    (  nonvar(String),
+       string_to_chars(String, Chars, string_codes/2),
+       '$skip_max_list'(_, -1, Chars, Tail),
+       Tail == [] ->
+       maplist(char_code, Chars, Codes)
+    ;  nonvar(Codes) ->
+       must_be(list, Codes),
+       maplist(char_code, Chars, Codes),
+       chars_to_string(Chars, String)
+    ;  instantiation_error(string_codes/2)
+    ).
+
+string_to_chars(String, Chars, PI) :-
+    '$skip_max_list'(_, -1, String, Tail),
+    (  String == [] ->
+       Chars = []
+    ;  atom(String) ->
+       atom_chars(String, Chars)
+    ;  ( Tail == [] ; var(Tail) ),
+       partial_chars(String) ->
+       Chars = String
+    ;  Tail == [],
+       codes_chars(String, Chars0) ->
+       Chars = Chars0
+    ;  type_error(string, String, PI)
+    ).
+
+must_be_string(String, PI) :-
+    '$skip_max_list'(_, -1, String, Tail),
+    (  ( Tail == [] ; var(Tail) ),
+       partial_chars(String) ->
+       true
+    ;  type_error(string, String, PI)
+    ).
+
+partial_chars(Cs) :-
+    var(Cs),
+    !.
+partial_chars([]).
+partial_chars([C|Cs]) :-
+    atom(C),
+    atom_length(C, 1),
+    partial_chars(Cs).
+
+codes_chars([], []).
+codes_chars([Code|Codes], [C|Cs]) :-
+    integer(Code),
+    catch(char_code(C, Code), error(representation_error(_), _), fail),
+    codes_chars(Codes, Cs).
+
+chars_to_string(Chars, String) :-
+    (  '$skip_max_list'(_, -1, Chars, Tail), Tail == [] ->
+       partial_string(Chars, String, [])
+    ;  String = Chars
+    ).
+
 :- dynamic(i_call_nth_nesting/2).
 :- dynamic(i_call_nth_counter/1).
 
@@ -215,3 +330,82 @@ call_nth_nesting(ID) :-
 
 copy_term_nat(Source, Dest) :-
     '$copy_term_without_attr_vars'(Source, Dest).
+
+
+%% the recorded database. Terms are stored under keys, whose principal
+%% functor alone is significant, and are identified by references of
+%% the form '$record'(N).
+
+:- dynamic(i_recorded/3).
+:- dynamic(i_record_counter/1).
+
+record_key(Key, PI, K) :-
+    (  var(Key) ->
+       instantiation_error(PI)
+    ;  compound(Key) ->
+       functor(Key, Name, Arity),
+       K = Name/Arity
+    ;  K = Key
+    ).
+
+next_record_ref('$record'(N)) :-
+    (  retract(i_record_counter(N0)) ->
+       N is N0 + 1
+    ;  N = 0
+    ),
+    assertz(i_record_counter(N)).
+
+recorda(Key, Term, Ref) :-
+    record_key(Key, recorda/3, K),
+    must_be_unbound_ref(Ref, recorda/3),
+    next_record_ref(Ref),
+    asserta(i_recorded(K, Ref, Term)).
+
+recordz(Key, Term, Ref) :-
+    record_key(Key, recordz/3, K),
+    must_be_unbound_ref(Ref, recordz/3),
+    next_record_ref(Ref),
+    assertz(i_recorded(K, Ref, Term)).
+
+must_be_unbound_ref(Ref, PI) :-
+    (  var(Ref) ->
+       true
+    ;  throw(error(uninstantiation_error(Ref), PI))
+    ).
+
+recorded(Key, Term, Ref) :-
+    (  var(Key) ->
+       i_recorded(_, Ref, Term)
+    ;  record_key(Key, recorded/3, K),
+       i_recorded(K, Ref, Term)
+    ).
+
+erase(Ref) :-
+    (  var(Ref) ->
+       instantiation_error(erase/1)
+    ;  retract(i_recorded(_, Ref, _)) ->
+       true
+    ;  throw(error(existence_error(db_reference, Ref), erase/1))
+    ).
+
+
+%% memo(Key, Goal) runs Goal the first time it is called with the
+%% ground key Key and records its solutions. Later calls with the same
+%% key unify Goal with the recorded solutions without running it.
+
+:- dynamic(i_memo_table/2).
+
+memo(Key, Goal) :-
+    (  \+ ground(Key) ->
+       instantiation_error(memo/2)
+    ;  i_memo_table(Key, Solutions) ->
+       true
+    ;  findall(Goal, Goal, Solutions),
+       assertz(i_memo_table(Key, Solutions))
+    ),
+    memo_solution(Solutions, Goal).
+
+memo_solution([Solution|Solutions], Goal) :-
+    (  Goal = Solution
+    ;  memo_solution(Solutions, Goal)
+    ).
@@ -1,9 +1,12 @@
 :- module(lists, [member/2, select/3, append/2, append/3, foldl/4, foldl/5,
-		          memberchk/2, reverse/2, length/2, maplist/2,
+		          memberchk/2, reverse/2, length/2, proper_length/2,
+		          maplist/2,
 		          maplist/3, maplist/4, maplist/5, maplist/6,
-		          maplist/7, maplist/8, maplist/9, same_length/2, nth0/3,
+		          maplist/7, maplist/8, maplist/9, same_length/2, nth0/3, nth1/3,
 		          sum_list/2, transpose/2, list_to_set/2, list_max/2,
-                          list_min/2, permutation/2]).
+                          list_min/2, permutation/2, subtract/3,
+                          intersection/3, union/3, flatten/2,
+                          predsort/3, msort/2]).
 
 /*  Author:        Mark Thom, Jan Wielemaker, and Richard O'Keefe
     Copyright (c)  2018-2021, Mark Thom
@@ -49,6 +52,7 @@
 
 :- meta_predicate foldl(3, ?, ?, ?).
 :- meta_predicate foldl(4, ?, ?, ?, ?).
+:- meta_predicate predsort(3, ?, ?).
 
 
 length(Xs, N) :-
@@ -79,6 +83,14 @@ length_rundown([_|Xs], N) :-
     N1 is N-1,
     length_rundown(Xs, N1).
 
+%% proper_length(List, Length) holds iff List is a proper list of
+%% length Length. Unlike length/2, it fails for partial and cyclic
+%% lists rather than generating or throwing.
+proper_length(List, Length) :-
+    '$skip_max_list'(Length0, -1, List, Tail),
+    Tail == [],
+    Length = Length0.
+
 
 member(X, [X|_]).
 member(X, [_|Xs]) :- member(X, Xs).
@@ -227,6 +239,66 @@ unify_same(E-V, Prev-Var, E-V) :-
         ).
 
 
+%% flatten(+NestedList, -FlatList)
+%
+% FlatList holds the non-list elements of NestedList, which may be
+% nested to any depth, in their order of appearance. [] contributes
+% no elements. Fails if NestedList is cyclic.
+
+flatten(List, FlatList) :-
+        acyclic_term(List),
+        flatten_(List, [], FlatList0),
+        !,
+        FlatList = FlatList0.
+
+flatten_(Var, Tl, [Var|Tl]) :-
+        var(Var),
+        !.
+flatten_([], Tl, Tl) :- !.
+flatten_([Hd|Tl], Tail, List) :-
+        !,
+        flatten_(Hd, FlatHeadTail, List),
+        flatten_(Tl, Tail, FlatHeadTail).
+flatten_(NonList, Tl, [NonList|Tl]).
+
+
+%% subtract(+Set, +Delete, -Result)
+%
+% Result holds the elements of Set that are not in Delete.
+% intersection/3 and union/3 likewise treat their arguments as
+% unordered sets, compare elements with ==/2 and keep the order of
+% the first list.
+
+subtract([], _, []).
+subtract([E|Es], Ds, Rs0) :-
+        (   memberchk_eq(E, Ds) ->
+            Rs0 = Rs
+        ;   Rs0 = [E|Rs]
+        ),
+        subtract(Es, Ds, Rs).
+
+intersection([], _, []).
+intersection([E|Es], Ls, Is0) :-
+        (   memberchk_eq(E, Ls) ->
+            Is0 = [E|Is]
+        ;   Is0 = Is
+        ),
+        intersection(Es, Ls, Is).
+
+union([], Ls, Ls).
+union([E|Es], Ls, Us0) :-
+        (   memberchk_eq(E, Ls) ->
+            Us0 = Us
+        ;   Us0 = [E|Us]
+        ),
+        union(Es, Ls, Us).
+
+memberchk_eq(X, [Y|Ys]) :-
+        (   X == Y -> true
+        ;   memberchk_eq(X, Ys)
+        ).
+
+
 nth0(N, Es, E) :-
         can_be(integer, N),
         can_be(list, Es),
@@ -249,6 +321,15 @@ nth0_search(N0, N, [_|Es], E) :-
         N1 is N0 + 1,
         nth0_search(N1, N, Es, E).
 
+nth1(N, Es, E) :-
+        can_be(integer, N),
+        can_be(list, Es),
+        (   integer(N) ->
+            N0 is N - 1,
+            nth0_index(N0, Es, E)
+        ;   nth0_search(1, N, Es, E)
+        ).
+
 
 list_max([N|Ns], Max) :-
     foldl(lists:list_max_, Ns, N, Max).
@@ -305,3 +386,76 @@ perm([], []).
 perm(List, [First|Perm]) :-
     select(First, List, Rest),
     perm(Rest, Perm).
+
+
+%!  predsort(:Pred, +List, -Sorted) is det.
+%
+%   Sorts List using Pred, called as call(Pred, Order, A, B), where
+%   Order is one of <, = or >. If Order is =, only the earlier of the
+%   two elements is kept.
+
+predsort(P, L, Sorted) :-
+    must_be_goal(P),
+    must_be(list, L),
+    length(L, N),
+    predsort(P, N, L, _, Sorted1),
+    !,
+    Sorted = Sorted1.
+
+must_be_goal(G) :-
+    (   nonvar(G), G = _:G0 ->
+        must_be_goal(G0)
+    ;   must_be(callable, G)
+    ).
+
+predsort(P, 2, [X1,X2|L], L, R) :-
+    !,
+    call(P, Delta, X1, X2),
+    sort2(Delta, X1, X2, R).
+predsort(_, 1, [X|L], L, [X]) :- !.
+predsort(_, 0, L, L, []) :- !.
+predsort(P, N, L1, L3, R) :-
+    N1 is N // 2,
+    N2 is N - N1,
+    predsort(P, N1, L1, L2, R1),
+    predsort(P, N2, L2, L3, R2),
+    predmerge(P, R1, R2, R).
+
+sort2(<, X1, X2, [X1,X2]).
+sort2(=, X1, _,  [X1]).
+sort2(>, X1, X2, [X2,X1]).
+
+predmerge(_, [], R, R) :- !.
+predmerge(_, R, [], R) :- !.
+predmerge(P, [H1|T1], [H2|T2], Result) :-
+    call(P, Delta, H1, H2),
+    !,
+    predmerge_(Delta, P, H1, H2, T1, T2, Result).
+
+predmerge_(<, P, H1, H2, T1, T2, [H1|R]) :-
+    predmerge(P, T1, [H2|T2], R).
+predmerge_(=, P, H1, _, T1, T2, [H1|R]) :-
+    predmerge(P, T1, T2, R).
+predmerge_(>, P, H1, H2, T1, T2, [H2|R]) :-
+    predmerge(P, [H1|T1], T2, R).
+
+
+%!  msort(+List, -Sorted) is det.
+%
+%   Sorts List into the standard order of terms like sort/2, but keeps
+%   duplicate elements.
+
+msort(Ls0, Ls) :-
+    must_be(list, Ls0),
+    can_be(list, Ls),
+    msort_pairs_(Ls0, Ps0),
+    keysort(Ps0, Ps),
+    msort_keys_(Ps, Ls).
+
+msort_pairs_([], []).
+msort_pairs_([L|Ls], [L-_|Ps]) :-
+    msort_pairs_(Ls, Ps).
+
+msort_keys_([], []).
+msort_keys_([L-_|Ps], [L|Ls]) :-
+    msort_keys_(Ps, Ls).
@@ -9,12 +9,22 @@
        ?- getenv("LANG", Ls).
           Ls = "en_US.UTF-8".
 
+   environ(Vs) yields all variables as a list of Key=Value pairs.
+   A host embedding Scryer may restrict or virtualize the environment
+   that these predicates see.
+
+   shell(Command, Status) runs Command in the system shell and yields
+   its exit status. Hosts must opt in to running commands; otherwise,
+   shell/2 throws a permission error.
+
    Public domain code.
 - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - */
 
 :- module(os, [getenv/2,
                setenv/2,
                unsetenv/1,
+               environ/1,
+               shell/2,
                pid/1]).
 
 :- use_module(library(error)).
@@ -28,11 +38,24 @@ getenv(Key, Value) :-
 setenv(Key, Value) :-
         must_be_env_var(Key),
         must_be_chars(Value),
-        '$setenv'(Key, Value).
+        (   '$setenv'(Key, Value) -> true
+        ;   throw(error(permission_error(modify, env_var, Key), setenv/2))
+        ).
 
 unsetenv(Key) :-
         must_be_env_var(Key),
-        '$unsetenv'(Key).
+        (   '$unsetenv'(Key) -> true
+        ;   throw(error(permission_error(modify, env_var, Key), unsetenv/1))
+        ).
+
+environ(Vs) :-
+        can_be(list, Vs),
+        '$environ'(Vs).
+
+shell(Command, Status) :-
+        must_be_chars(Command),
+        can_be(integer, Status),
+        '$shell'(Command, Status).
 
 pid(PID) :-
         can_be(integer, PID),
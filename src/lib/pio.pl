@@ -1,5 +1,6 @@
 :- module(pio, [phrase_from_file/2,
-                phrase_from_file/3]).
+                phrase_from_file/3,
+                stream_to_lazy_list/2]).
 
 :- use_module(library(dcgs)).
 :- use_module(library(error)).
@@ -31,6 +32,13 @@ phrase_from_file(NT, File, Options) :-
    ).
 
 
+%% stream_to_lazy_list(+Stream, -Xs)
+%
+% Xs is a lazy list of the characters of Stream. Nothing is read
+% until Xs is inspected, after which the stream is consumed in blocks
+% of at most 4096 characters, each block only when the list is
+% unified past the characters read so far.
+
 stream_to_lazy_list(Stream, Xs) :-
         stream_property(Stream, position(Pos)),
         freeze(Xs, reader_step(Stream, Pos, Xs)).
@@ -1,4 +1,6 @@
-:- module(random, [maybe/0, random/1, random_integer/3, set_random/1]).
+:- module(random, [maybe/0, random/1, random_between/3, random_integer/3,
+                   random_member/2, random_permutation/2,
+                   set_random/1]).
 
 /* - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - -
    To retain desirable declarative properties, predicates that internally
@@ -7,6 +9,8 @@
 - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - */
 
 :- use_module(library(error)).
+:- use_module(library(iso_ext), [setarg/3]).
+:- use_module(library(lists), [length/2, nth0/3]).
 
 % succeeds with probability 0.5.
 maybe :- '$maybe'.
@@ -32,6 +36,42 @@ random_integer(Lower, Upper, R) :-
         R is floor((Upper - Lower) * R0 + Lower)
     ).
 
+% like random_integer/3, but Upper is included in the range.
+random_between(Lower, Upper, R) :-
+    must_be(integer, Lower),
+    must_be(integer, Upper),
+    Upper >= Lower,
+    Upper1 is Upper + 1,
+    random_integer(Lower, Upper1, R).
+
+random_member(X, List) :-
+    must_be(list, List),
+    length(List, N),
+    N > 0,
+    random_integer(0, N, I),
+    nth0(I, List, X).
+
+% Fisher-Yates: the element at each position from the last down to the
+% second is swapped with one at a random position no greater than it.
+random_permutation(List, Perm) :-
+    must_be(list, List),
+    length(List, N),
+    Term =.. [perm|List],
+    shuffle_(N, Term),
+    Term =.. [_|Perm].
+
+shuffle_(I, Term) :-
+    (   I =< 1 -> true
+    ;   I1 is I + 1,
+        random_integer(1, I1, J),
+        arg(I, Term, X),
+        arg(J, Term, Y),
+        setarg(I, Term, Y),
+        setarg(J, Term, X),
+        I0 is I - 1,
+        shuffle_(I0, Term)
+    ).
+
 rnd(N, R) :-
     rnd_(N, 0, R).
 
@@ -14,7 +14,8 @@ parse_socket_options_(Option, OptionPair) :-
     builtins:parse_stream_options_(Option, OptionPair).
 
 parse_socket_options(Options, OptionValues, Stub) :-
-    DefaultOptions = [alias-[], eof_action-eof_code, reposition-false, tls-false, type-text],
+    DefaultOptions = [alias-[], encoding-utf8, eof_action-eof_code, reposition-false,
+                      tls-false, type-text],
     builtins:parse_options_list(Options, sockets:parse_socket_options_, DefaultOptions, OptionValues, Stub).
 
 socket_client_open(Addr, Stream, Options) :-
@@ -33,8 +34,9 @@ socket_client_open(Addr, Stream, Options) :-
        throw(error(type_error(socket_address, Addr), socket_client_open/3))
     ),
     parse_socket_options(Options,
-                         [Alias, EOFAction, Reposition, TLS, Type],
+                         [Alias, Encoding, EOFAction, Reposition, TLS, Type],
                          socket_client_open/3),
+    must_be_utf8(Encoding, socket_client_open/3),
     '$socket_client_open'(Address, Port, Stream, Alias, EOFAction, Reposition, Type, TLS).
 
 
@@ -54,11 +56,22 @@ socket_server_accept(ServerSocket, Client, Stream, Options) :-
     must_be(var, Client),
     must_be(var, Stream),
     builtins:parse_stream_options(Options,
-                                  [Alias, EOFAction, Reposition, Type],
+                                  [Alias, Encoding, EOFAction, Reposition, Type],
                                   socket_server_accept/4),
+    must_be_utf8(Encoding, socket_server_accept/4),
     '$socket_server_accept'(ServerSocket, Client, Stream, Alias, EOFAction, Reposition, Type).
 
 
+%% sockets are written to as well as read from, and the octet encoding
+%% is only supported for input.
+
+must_be_utf8(Encoding, PI) :-
+    (  Encoding == utf8 ->
+       true
+    ;  throw(error(domain_error(stream_option, encoding(Encoding)), PI))
+    ).
+
+
 socket_server_close(ServerSocket) :-
     '$socket_server_close'(ServerSocket).
 
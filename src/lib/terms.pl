@@ -1,4 +1,4 @@
-:- module(terms, [numbervars/3]).
+:- module(terms, [numbervars/3, term_hash/2, term_hash/4]).
 
 :- use_module(library(error)).
 
@@ -17,3 +17,118 @@ numberlist([], N, N).
 numberlist(['$VAR'(N0)|Vars], N0, N) :-
    N1 is N0+1,
    numberlist(Vars, N1, N).
+
+%% term_hash(+Term, -Hash)
+%
+% Hash is an integer in 0..2^32-1 computed from the structure of
+% Term. It depends only on the atoms, numbers and functors occurring
+% in Term, never on where Term lives on the heap, so equal ground terms
+% always hash equally. If Term is not ground, Hash is left unbound.
+% Term must be acyclic.
+
+term_hash(Term, Hash) :-
+   must_be_acyclic(Term, term_hash/2),
+   (  ground(Term) ->
+      hash_term(Term, -1, 2166136261, Hash0),
+      hash_finish(Hash0, Hash)
+   ;  true
+   ).
+
+%% term_hash(+Term, +Depth, +Range, -Hash)
+%
+% Like term_hash/2, but only the subterms of Term up to depth Depth
+% are hashed and Hash lies in 0..Range-1. Depth -1 hashes all of Term,
+% which must then be acyclic. Hash is left unbound if a variable occurs
+% within Depth.
+
+term_hash(Term, Depth, Range, Hash) :-
+   must_be(integer, Depth),
+   must_be(integer, Range),
+   (  Depth < -1 ->
+      domain_error(depth, Depth, term_hash/4)
+   ;  Range < 1 ->
+      domain_error(range, Range, term_hash/4)
+   ;  Depth =:= -1 ->
+      must_be_acyclic(Term, term_hash/4)
+   ;  true
+   ),
+   (  hash_term(Term, Depth, 2166136261, Hash0) ->
+      hash_finish(Hash0, Hash1),
+      Hash is Hash1 mod Range
+   ;  true
+   ).
+
+must_be_acyclic(Term, Context) :-
+   (  acyclic_term(Term) ->
+      true
+   ;  type_error(acyclic_term, Term, Context)
+   ).
+
+% fails if a variable occurs within Depth.
+hash_term(_, 0, H, H) :- !.
+hash_term(Term, Depth0, H0, H) :-
+   nonvar(Term),
+   Depth is Depth0 - 1,
+   (  atom(Term) ->
+      atom_codes(Term, Cs),
+      hash_mix(H0, 1, H1),
+      hash_codes(Cs, H1, H)
+   ;  integer(Term) ->
+      hash_mix(H0, 2, H1),
+      hash_integer(Term, H1, H)
+   ;  number(Term) ->
+      number_codes(Term, Cs),
+      hash_mix(H0, 3, H1),
+      hash_codes(Cs, H1, H)
+   ;  functor(Term, Name, Arity),
+      atom_codes(Name, Cs),
+      hash_mix(H0, 4, H1),
+      hash_codes(Cs, H1, H2),
+      hash_mix(H2, Arity, H3),
+      Term =.. [_|Args],
+      hash_args(Args, Depth, H3, H)
+   ).
+
+hash_args([], _, H, H).
+hash_args([Arg|Args], Depth, H0, H) :-
+   hash_term(Arg, Depth, H0, H1),
+   hash_args(Args, Depth, H1, H).
+
+hash_codes([], H, H).
+hash_codes([C|Cs], H0, H) :-
+   hash_mix(H0, C, H1),
+   hash_codes(Cs, H1, H).
+
+% integers of any size are hashed 32 bits at a time, least
+% significant first, ending with the sign.
+hash_integer(I, H0, H) :-
+   (  I >= 0, I =< 4294967295 ->
+      hash_mix(H0, I, H1),
+      hash_mix(H1, 0, H)
+   ;  I < 0, I >= -4294967296 ->
+      Low is I /\ 4294967295,
+      hash_mix(H0, Low, H1),
+      hash_mix(H1, 1, H)
+   ;  Low is I /\ 4294967295,
+      I1 is I >> 32,
+      hash_mix(H0, Low, H1),
+      hash_integer(I1, H1, H)
+   ).
+
+% one step of 32-bit FNV-1a, mixing in X, a value of 0..2^32-1, a
+% byte at a time.
+hash_mix(H0, X, H) :-
+   hash_byte(H0, X /\ 255, H1),
+   hash_byte(H1, (X >> 8) /\ 255, H2),
+   hash_byte(H2, (X >> 16) /\ 255, H3),
+   hash_byte(H3, (X >> 24) /\ 255, H).
+
+hash_byte(H0, B, H) :-
+   H is ((H0 xor B) * 16777619) /\ 4294967295.
+
+% the final avalanche of MurmurHash3, so that terms differing only in
+% their last few bits still spread over the whole range.
+hash_finish(H0, H) :-
+   H1 is ((H0 xor (H0 >> 16)) * 2246822507) /\ 4294967295,
+   H2 is ((H1 xor (H1 >> 13)) * 3266489909) /\ 4294967295,
+   H is H2 xor (H2 >> 16).
@@ -36,13 +36,22 @@
      ?- current_time(T), phrase(format_time("%d.%m.%Y (%H:%M:%S)", T), Cs).
         T = [...], Cs = "11.06.2020 (00:24:32)".
 
+   get_time(T) yields the current system time as the number of seconds
+   since the Unix epoch (a floating point number).
+
+   format_time(Out, FormatString, TimeStamp) writes the characters
+   described by format_time//2 to Out, which is either a stream,
+   atom(A) or chars(Cs). TimeStamp is either as obtained by
+   current_time/1 or a number of seconds as obtained by get_time/1.
+
    sleep(S) sleeps for S seconds (a floating point number).
 
    time(Goal) reports the execution time of Goal.
 
 - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - - */
 
-:- module(time, [max_sleep_time/1, sleep/1, time/1, current_time/1, format_time//2]).
+:- module(time, [max_sleep_time/1, sleep/1, time/1, current_time/1,
+                 format_time//2, format_time/3, get_time/1]).
 
 :- use_module(library(format)).
 :- use_module(library(iso_ext)).
@@ -55,6 +64,31 @@ current_time(T) :-
         '$current_time'(T0),
         read_term_from_chars(T0, T).
 
+get_time(T) :-
+        '$get_time'(T).
+
+format_time(Out, FormatString, TimeStamp) :-
+        (   var(Out) ->
+            instantiation_error(format_time/3)
+        ;   var(TimeStamp) ->
+            instantiation_error(format_time/3)
+        ;   number(TimeStamp) ->
+            '$timestamp_to_time'(TimeStamp, T0),
+            read_term_from_chars(T0, T)
+        ;   T = TimeStamp
+        ),
+        (   atom(FormatString) ->
+            atom_chars(FormatString, Fs)
+        ;   Fs = FormatString
+        ),
+        phrase(format_time(Fs, T), Cs),
+        (   Out = atom(A) ->
+            atom_chars(A, Cs)
+        ;   Out = chars(Cs0) ->
+            Cs0 = Cs
+        ;   format(Out, "~s", [Cs])
+        ).
+
 format_time([], _) --> [].
 format_time(['%','%'|Fs], T) --> !, "%", format_time(Fs, T).
 format_time(['%',Spec|Fs], T) --> !,
@@ -18,7 +18,9 @@
 
 
 '$print_message_and_fail'(Error) :-
-    (  (  Error = error(existence_error(procedure, Expansion), Expansion)
+    (  Error == '$aborted' ->
+       true
+    ;  (  Error = error(existence_error(procedure, Expansion), Expansion)
        ;  Error = error(evaluation_error((_:_)/_),Expansion)
        )  ->
        (  (  Expansion = goal_expansion/2
@@ -35,10 +37,32 @@
     ),
     '$fail'.
 
+% Expansion hooks that are simply undefined fail silently. Any other
+% error thrown by a hook is wrapped in an expansion_error/2 naming the
+% hook goal and, during a load, the line of the term being expanded.
+'$expansion_error'(Error, Goal) :-
+    (  (  Error = error(existence_error(procedure, Expansion), Expansion)
+       ;  Error = error(evaluation_error((_:_)/_), Expansion)
+       ),
+       (  Expansion = goal_expansion/2
+       ;  Expansion = term_expansion/2
+       )  ->
+       '$fail'
+    ;  (  catch(prolog_load_context(term_position,
+                                    position_and_lines_read(_, Line)),
+                _,
+                false),
+          integer(Line) ->
+          Context = load/1:Line
+       ;  Context = load/1
+       ),
+       throw(error(expansion_error(Goal, Error), Context))
+    ).
+
 expand_term(Term, ExpandedTerm) :-
     (  catch('$call'(user:term_expansion(Term, ExpandedTerm0)),
              E,
-             '$call'(loader:'$print_message_and_fail'(E))) ->
+             '$call'(loader:'$expansion_error'(E, user:term_expansion(Term, _)))) ->
        (  var(ExpandedTerm0) ->
           error:instantiation_error(term_expansion/2)
        ;  ExpandedTerm0 = [_|_] ->
@@ -65,7 +89,7 @@ goal_expansion(Goal, Module, ExpandedGoal) :-
     (  atom(Module),
        catch('$call'(Module:goal_expansion(Goal, ExpandedGoal0)),
              E,
-             '$call'(loader:'$print_message_and_fail'(E))) ->
+             '$call'(loader:'$expansion_error'(E, Module:goal_expansion(Goal, _)))) ->
        (  var(ExpandedGoal0) ->
           error:instantiation_error(goal_expansion/2)
        ;  goal_expansion(ExpandedGoal0, Module, ExpandedGoal)
@@ -75,6 +99,46 @@ goal_expansion(Goal, Module, ExpandedGoal) :-
 
 
 
+% Applies goal_expansion/2 to the goals of a clause body added by
+% asserta/1 or assertz/1. Unlike expand_goal/3, meta-predicate
+% arguments are left unqualified so that clause/2 retrieves the
+% asserted body unchanged but for the expansions.
+
+expand_asserted_body(Body0, _, Body) :-
+    var(Body0),
+    !,
+    Body = Body0.
+expand_asserted_body((Goal0, Goals0), Module, (Goal, Goals)) :-
+    !,
+    expand_asserted_body(Goal0, Module, Goal),
+    expand_asserted_body(Goals0, Module, Goals).
+expand_asserted_body((Goals0 -> Goals1), Module, (ExpandedGoals0 -> ExpandedGoals1)) :-
+    !,
+    expand_asserted_body(Goals0, Module, ExpandedGoals0),
+    expand_asserted_body(Goals1, Module, ExpandedGoals1).
+expand_asserted_body((Goals0 ; Goals1), Module, (ExpandedGoals0 ; ExpandedGoals1)) :-
+    !,
+    expand_asserted_body(Goals0, Module, ExpandedGoals0),
+    expand_asserted_body(Goals1, Module, ExpandedGoals1).
+expand_asserted_body((\+ Goals0), Module, (\+ Goals)) :-
+    !,
+    expand_asserted_body(Goals0, Module, Goals).
+expand_asserted_body(Module:Goals0, _, Module:Goals) :-
+    atom(Module),
+    !,
+    expand_asserted_body(Goals0, Module, Goals).
+expand_asserted_body(Goal0, Module, Goal) :-
+    goal_expansion(Goal0, Module, Goal1),
+    (  Module \== user ->
+       goal_expansion(Goal1, user, Goal2)
+    ;  Goal2 = Goal1
+    ),
+    (  Goal2 == Goal0 ->
+       Goal = Goal0
+    ;  expand_asserted_body(Goal2, Module, Goal)
+    ).
+
+
 create_file_load_context(Stream, Path, Evacuable) :-
     '$push_load_context'(Stream, Path),
     '$push_load_state_payload'(Evacuable).
@@ -92,13 +156,20 @@ run_initialization_goals :-
     (  predicate_property(Module:'$initialization_goals'(_), dynamic) ->
        findall(Module:Goal, '$call'(builtins:retract(Module:'$initialization_goals'(Goal))), Goals),
        abolish(Module:'$initialization_goals'/1),
-       (  maplist(Module:call, Goals) ->
-          true
-       ;  true %% initialization goals can fail without thwarting the load.
-       )
+       maplist(loader:run_initialization_goal, Goals)
     ;  true
     ).
 
+%% initialization goals can fail without thwarting the load, but each
+%% failure is reported, and the goals after it are still run.
+run_initialization_goal(Module:Goal) :-
+    (  call(Module:Goal) ->
+       true
+    ;  write('Warning: initialization goal failed: '),
+       writeq(Goal),
+       nl
+    ).
+
 file_load(Stream, Path) :-
     file_load(Stream, Path, _),
     false.        %% Clear the heap.
@@ -493,6 +564,28 @@ open_file(Path, Stream) :-
             )
     ).
 
+% Libraries not bundled with Scryer are searched for in the library
+% paths the machine was built with, then in the directories given by
+% the user-defined library_directory/1 hook, in the order of its
+% solutions.
+library_search_directory(Dir) :-
+    '$library_paths'(Dirs),
+    member(Dir, Dirs).
+library_search_directory(Dir) :-
+    catch(user:library_directory(Dir),
+          error(existence_error(procedure, _), _),
+          false).
+
+open_library_directory_file(LibraryPath, Stream) :-
+    library_search_directory(Dir),
+    atom(Dir),
+    atom_concat(Dir, '/', DirSlashed),
+    atom_concat(DirSlashed, LibraryPath, Path),
+    catch(open_file(Path, Stream),
+          error(existence_error(source_sink, _), _),
+          false),
+    !.
+
 use_module(Module, Exports, Evacuable) :-
     (  var(Module) ->
        instantiation_error(load/1)
@@ -500,9 +593,17 @@ use_module(Module, Exports, Evacuable) :-
        (  path_atom(Library, LibraryPath) ->
           (  '$load_compiled_library'(LibraryPath, Exports, Evacuable) ->
              true
-          ;  '$load_library_as_stream'(LibraryPath, Stream, Path),
+          ;  catch('$load_library_as_stream'(LibraryPath, Stream, Path),
+                   error(existence_error(_, _), _),
+                   false) ->
              file_load(Stream, Path, Subevacuable),
              '$use_module'(Evacuable, Subevacuable, Exports)
+          ;  open_library_directory_file(LibraryPath, Stream) ->
+             stream_property(Stream, file_name(PathFileName)),
+             file_load(Stream, PathFileName, Subevacuable),
+             '$use_module'(Evacuable, Subevacuable, Exports)
+          ;  % rethrow the existence error for the bundled library.
+             '$load_library_as_stream'(LibraryPath, _, _)
           )
        ;  var(Library) ->
           instantiation_error(load/1)
@@ -510,7 +611,9 @@ use_module(Module, Exports, Evacuable) :-
        )
     ;  (  path_atom(Module, ModulePath) ->
           load_context_path(ModulePath, Path),
-          open_file(Path, Stream),
+          catch(open_file(Path, Stream),
+                error(existence_error(source_sink, _), _),
+                throw(error(existence_error(source_sink, Module), load/1))),
           stream_property(Stream, file_name(PathFileName)),
           file_load(Stream, PathFileName, Subevacuable),
           '$use_module'(Evacuable, Subevacuable, Exports)
@@ -12,7 +12,6 @@ use crate::machine::machine_state::*;
 use crate::rug::{Integer, Rational};
 use ordered_float::*;
 
-use std::cmp;
 use std::convert::TryFrom;
 use std::f64;
 use std::mem;
@@ -159,7 +158,10 @@ impl MachineState {
                         "xor" => interms.push(self.xor(a1, a2)?),
                         "mod" => interms.push(self.modulus(a1, a2)?),
                         "rem" => interms.push(self.remainder(a1, a2)?),
-                        "atan2" => interms.push(Number::Float(OrderedFloat(self.atan2(a1, a2)?))),
+                        "atan2" | "atan" => {
+                            interms.push(Number::Float(OrderedFloat(self.atan2(a1, a2)?)))
+                        }
+                        "log" => interms.push(Number::Float(OrderedFloat(self.log_base(a1, a2)?))),
                         "gcd" => interms.push(self.gcd(a1, a2)?),
                         _ => {
                             let evaluable_stub = MachineError::functor_stub(name.clone(), 2);
@@ -198,6 +200,8 @@ impl MachineState {
                         "floor" => interms.push(self.floor(a1)),
                         "\\" => interms.push(self.bitwise_complement(a1)?),
                         "sign" => interms.push(self.sign(a1)),
+                        "msb" => interms.push(self.msb(a1)?),
+                        "lsb" => interms.push(self.lsb(a1)?),
                         _ => {
                             let evaluable_stub = MachineError::functor_stub(name.clone(), 1);
 
@@ -380,6 +384,18 @@ impl MachineState {
         }
     }
 
+    pub(crate) fn log_base(&self, n1: Number, n2: Number) -> Result<f64, MachineStub> {
+        let base = self.log(n1)?;
+        let n = self.log(n2)?;
+
+        if base == 0f64 {
+            let stub = MachineError::functor_stub(clause_name!("is"), 2);
+            return Err(self.error_form(MachineError::evaluation_error(EvalError::Undefined), stub));
+        }
+
+        self.unary_float_fn_template(Number::Float(OrderedFloat(n)), |f| f / base)
+    }
+
     pub(crate) fn int_pow(&self, n1: Number, n2: Number) -> Result<Number, MachineStub> {
         if n1.is_zero() && n2.is_negative() {
             let stub = MachineError::functor_stub(clause_name!("is"), 2);
@@ -539,7 +555,30 @@ impl MachineState {
             return Err(self.error_form(MachineError::evaluation_error(EvalError::Undefined), stub));
         }
 
-        self.float_pow(n1, n2)
+        match (&n1, &n2) {
+            (&Number::Fixnum(_), &Number::Fixnum(_))
+            | (&Number::Fixnum(_), &Number::Integer(_))
+            | (&Number::Integer(_), &Number::Fixnum(_))
+            | (&Number::Integer(_), &Number::Integer(_)) => {
+                if !n2.is_negative() {
+                    return self.int_pow(n1, n2);
+                }
+
+                // a negative integer exponent yields the exact rational
+                // 1 / (n1 ** -n2), which is an integer only for n1 = 1, -1.
+                match self.int_pow(n1, -n2)? {
+                    Number::Fixnum(n) if n == 1 || n == -1 => Ok(Number::Fixnum(n)),
+                    Number::Fixnum(n) => Ok(Number::Rational(Rc::new(Rational::from(
+                        &Rational::from(1) / &Rational::from(n),
+                    )))),
+                    Number::Integer(n) => Ok(Number::Rational(Rc::new(Rational::from(
+                        &Rational::from(1) / &Rational::from(&*n),
+                    )))),
+                    _ => unreachable!(),
+                }
+            }
+            _ => self.float_pow(n1, n2),
+        }
     }
 
     #[inline]
@@ -576,6 +615,11 @@ impl MachineState {
 
     #[inline]
     pub(crate) fn log(&self, n1: Number) -> Result<f64, MachineStub> {
+        if !n1.is_positive() || n1.is_zero() {
+            let stub = MachineError::functor_stub(clause_name!("is"), 2);
+            return Err(self.error_form(MachineError::evaluation_error(EvalError::Undefined), stub));
+        }
+
         self.unary_float_fn_template(n1, |f| f.log(f64::consts::E))
     }
 
@@ -601,7 +645,7 @@ impl MachineState {
 
     #[inline]
     pub(crate) fn sqrt(&self, n1: Number) -> Result<f64, MachineStub> {
-        if n1.is_negative() {
+        if n1.is_negative() && !n1.is_zero() {
             let stub = MachineError::functor_stub(clause_name!("is"), 2);
             return Err(self.error_form(MachineError::evaluation_error(EvalError::Undefined), stub));
         }
@@ -646,6 +690,13 @@ impl MachineState {
     pub(crate) fn shr(&self, n1: Number, n2: Number) -> Result<Number, MachineStub> {
         let stub = MachineError::functor_stub(clause_name!("(>>)"), 2);
 
+        // a negative shift count shifts in the opposite direction.
+        if let Number::Fixnum(_) | Number::Integer(_) = n2 {
+            if n2.is_negative() {
+                return self.shl(n1, -n2);
+            }
+        }
+
         match (n1, n2) {
             (Number::Fixnum(n1), Number::Fixnum(n2)) => {
                 let n1 = Integer::from(n1);
@@ -690,6 +741,13 @@ impl MachineState {
     pub(crate) fn shl(&self, n1: Number, n2: Number) -> Result<Number, MachineStub> {
         let stub = MachineError::functor_stub(clause_name!("(<<)"), 2);
 
+        // a negative shift count shifts in the opposite direction.
+        if let Number::Fixnum(_) | Number::Integer(_) = n2 {
+            if n2.is_negative() {
+                return self.shr(n1, -n2);
+            }
+        }
+
         match (n1, n2) {
             (Number::Fixnum(n1), Number::Fixnum(n2)) => {
                 let n1 = Integer::from(n1);
@@ -732,7 +790,7 @@ impl MachineState {
     }
 
     pub(crate) fn bitwise_complement(&self, n1: Number) -> Result<Number, MachineStub> {
-        let stub = MachineError::functor_stub(clause_name!("(\\)"), 2);
+        let stub = MachineError::functor_stub(clause_name!("(\\)"), 1);
 
         match n1 {
             Number::Fixnum(n) => Ok(Number::Fixnum(!n)),
@@ -921,88 +979,30 @@ impl MachineState {
     }
 
     pub(crate) fn max(&self, n1: Number, n2: Number) -> Result<Number, MachineStub> {
-        match (n1, n2) {
-            (Number::Fixnum(n1), Number::Fixnum(n2)) => {
-                if n1 > n2 {
-                    Ok(Number::Fixnum(n1))
-                } else {
-                    Ok(Number::Fixnum(n2))
-                }
-            }
-            (Number::Fixnum(n1), Number::Integer(n2)) => {
-                if &*n2 > &n1 {
-                    Ok(Number::Integer(n2))
-                } else {
-                    Ok(Number::Fixnum(n1))
-                }
-            }
-            (Number::Integer(n1), Number::Fixnum(n2)) => {
-                if &*n1 > &n2 {
-                    Ok(Number::Integer(n1))
-                } else {
-                    Ok(Number::Fixnum(n2))
-                }
-            }
-            (Number::Integer(n1), Number::Integer(n2)) => {
-                if n1 > n2 {
-                    Ok(Number::Integer(n1))
-                } else {
-                    Ok(Number::Integer(n2))
-                }
-            }
-            (n1, n2) => {
-                let stub = MachineError::functor_stub(clause_name!("max"), 2);
-
-                let f1 = try_numeric_result!(self, result_f(&n1, rnd_f), stub)?;
-                let f2 = try_numeric_result!(self, result_f(&n2, rnd_f), stub)?;
-
-                Ok(Number::Float(cmp::max(OrderedFloat(f1), OrderedFloat(f2))))
-            }
+        if n1 > n2 {
+            Ok(n1)
+        } else {
+            Ok(n2)
         }
     }
 
     pub(crate) fn min(&self, n1: Number, n2: Number) -> Result<Number, MachineStub> {
-        match (n1, n2) {
-            (Number::Fixnum(n1), Number::Fixnum(n2)) => {
-                if n1 < n2 {
-                    Ok(Number::Fixnum(n1))
-                } else {
-                    Ok(Number::Fixnum(n2))
-                }
-            }
-            (Number::Fixnum(n1), Number::Integer(n2)) => {
-                if &*n2 < &n1 {
-                    Ok(Number::Integer(n2))
-                } else {
-                    Ok(Number::Fixnum(n1))
-                }
-            }
-            (Number::Integer(n1), Number::Fixnum(n2)) => {
-                if &*n1 < &n2 {
-                    Ok(Number::Integer(n1))
-                } else {
-                    Ok(Number::Fixnum(n2))
-                }
-            }
-            (Number::Integer(n1), Number::Integer(n2)) => {
-                if n1 < n2 {
-                    Ok(Number::Integer(n1))
-                } else {
-                    Ok(Number::Integer(n2))
-                }
-            }
-            (n1, n2) => {
-                let stub = MachineError::functor_stub(clause_name!("max"), 2);
-
-                let f1 = try_numeric_result!(self, result_f(&n1, rnd_f), stub)?;
-                let f2 = try_numeric_result!(self, result_f(&n2, rnd_f), stub)?;
-
-                Ok(Number::Float(cmp::min(OrderedFloat(f1), OrderedFloat(f2))))
-            }
+        if n1 < n2 {
+            Ok(n1)
+        } else {
+            Ok(n2)
         }
     }
 
     pub(crate) fn sign(&self, n: Number) -> Number {
+        if let Number::Float(f) = n {
+            if f.is_nan() || *f == 0.0 {
+                return Number::Float(f);
+            }
+
+            return Number::Float(OrderedFloat(f.signum()));
+        }
+
         if n.is_positive() {
             Number::from(1)
         } else if n.is_negative() {
@@ -1011,4 +1011,45 @@ impl MachineState {
             Number::from(0)
         }
     }
+
+    pub(crate) fn msb(&self, n: Number) -> Result<Number, MachineStub> {
+        let stub = MachineError::functor_stub(clause_name!("msb"), 1);
+
+        match n {
+            Number::Fixnum(n) if n > 0 => {
+                let bits = (std::mem::size_of::<isize>() * 8) as u32;
+                Ok(Number::from((bits - 1 - n.leading_zeros()) as isize))
+            }
+            Number::Integer(ref i) if &**i > &0 => {
+                Ok(Number::from((i.significant_bits() - 1) as isize))
+            }
+            Number::Fixnum(_) | Number::Integer(_) => Err(self.error_form(
+                MachineError::domain_error(DomainErrorType::NotLessThanOne, n),
+                stub,
+            )),
+            _ => Err(self.error_form(
+                MachineError::type_error(self.heap.h(), ValidType::Integer, n),
+                stub,
+            )),
+        }
+    }
+
+    pub(crate) fn lsb(&self, n: Number) -> Result<Number, MachineStub> {
+        let stub = MachineError::functor_stub(clause_name!("lsb"), 1);
+
+        match n {
+            Number::Fixnum(n) if n > 0 => Ok(Number::from(n.trailing_zeros() as isize)),
+            Number::Integer(ref i) if &**i > &0 => {
+                Ok(Number::from(i.find_one(0).unwrap() as isize))
+            }
+            Number::Fixnum(_) | Number::Integer(_) => Err(self.error_form(
+                MachineError::domain_error(DomainErrorType::NotLessThanOne, n),
+                stub,
+            )),
+            _ => Err(self.error_form(
+                MachineError::type_error(self.heap.h(), ValidType::Integer, n),
+                stub,
+            )),
+        }
+    }
 }
@@ -30,6 +30,7 @@ pub(super) fn bootstrapping_compile(
         stream,
         wam.machine_st.atom_tbl.clone(),
         wam.machine_st.flags,
+        wam.machine_st.char_conversions.clone(),
         listing_src,
     );
 
@@ -1303,29 +1304,38 @@ fn mergeable_indexed_subsequences(
     false
 }
 
-fn print_overwrite_warning(
+fn overwrite_warning(
     compilation_target: &CompilationTarget,
     code_ptr: IndexPtr,
     key: &PredicateKey,
     is_dynamic: bool,
-) {
+) -> Option<LoadWarning> {
     if let CompilationTarget::Module(ref module_name) = compilation_target {
         match module_name.as_str() {
-            "builtins" | "loader" => return,
+            "builtins" | "loader" => return None,
             _ => {}
         }
     }
 
     match code_ptr {
-        IndexPtr::DynamicUndefined | IndexPtr::Undefined => return,
-        _ if is_dynamic => return,
+        IndexPtr::DynamicUndefined | IndexPtr::Undefined => return None,
+        _ if is_dynamic => return None,
         _ => {}
     }
 
-    println!("Warning: overwriting {}/{}", key.0, key.1);
+    Some(LoadWarning::Overwriting(key.0.as_str().to_string(), key.1))
 }
 
 impl<'a> LoadState<'a> {
+    // warnings are handed to the caller of Machine::consult_string if
+    // it is collecting them, and printed otherwise.
+    pub(super) fn warn(&mut self, warning: LoadWarning) {
+        match self.wam.load_warnings {
+            Some(ref mut warnings) => warnings.push(warning),
+            None => println!("Warning: {}", warning),
+        }
+    }
+
     pub(super) fn listing_src_file_name(&self) -> Option<ClauseName> {
         if let Some(load_context) = self.wam.load_contexts.last() {
             if !load_context.path.is_file() {
@@ -1463,12 +1473,14 @@ impl<'a> LoadState<'a> {
             );
         }
 
-        print_overwrite_warning(
+        if let Some(warning) = overwrite_warning(
             &predicates.compilation_target,
             code_index.get(),
             &key,
             settings.is_dynamic(),
-        );
+        ) {
+            self.warn(warning);
+        }
 
         let index_ptr = if settings.is_dynamic() {
             IndexPtr::DynamicIndex(code_ptr)
@@ -2176,6 +2188,19 @@ impl<'a, TS: TermStream> Loader<'a, TS> {
             })
             .ok_or(SessionError::NamelessEntry)?;
 
+        // the clauses of a text loaded in chunks add to those of the
+        // earlier chunks rather than replace them.
+        if self.load_state.wam.chunked_load
+            && self.load_state.wam.load_contexts.len() == 1
+            && self.load_state.compilation_target == self.predicates.compilation_target
+        {
+            self.add_discontiguous_predicate(
+                self.predicates.compilation_target.clone(),
+                key.0.clone(),
+                key.1,
+            )?;
+        }
+
         let mut predicate_info = self
             .load_state
             .wam
@@ -2225,11 +2250,13 @@ impl<'a, TS: TermStream> Loader<'a, TS> {
             if self.load_state.compilation_target != self.predicates.compilation_target {
                 if !local_predicate_info.is_extensible {
                     if predicate_info.is_multifile {
-                        println!(
-                            "Warning: overwriting multifile predicate {}:{}/{} because \
-                                  it was not locally declared multifile.",
-                            self.predicates.compilation_target, key.0, key.1
+                        let warning = LoadWarning::OverwritingMultifile(
+                            self.predicates.compilation_target.to_string(),
+                            key.0.as_str().to_string(),
+                            key.1,
                         );
+
+                        self.load_state.warn(warning);
                     }
 
                     if let Some(skeleton) = self
@@ -4,6 +4,7 @@ use crate::machine::term_stream::*;
 use crate::machine::*;
 
 use prolog_parser::clause_name;
+use prolog_parser::parser::get_op_desc;
 
 use indexmap::IndexSet;
 use ref_thread_local::RefThreadLocal;
@@ -707,7 +708,12 @@ impl<'a> LoadState<'a> {
         }
     }
 
-    pub(super) fn add_op_decl(&mut self, op_decl: &OpDecl) {
+    pub(super) fn add_op_decl(&mut self, op_decl: &OpDecl) -> Result<(), SessionError> {
+        if op_decl.prec > 0 {
+            let existing_desc = get_op_desc(op_decl.name.clone(), &self.composite_op_dir());
+            op_decl.check_fixity(existing_desc)?;
+        }
+
         match &self.compilation_target {
             CompilationTarget::User => {
                 if let Some(filename) = self.listing_src_file_name() {
@@ -744,6 +750,8 @@ impl<'a> LoadState<'a> {
                 }
             }
         }
+
+        Ok(())
     }
 
     pub(super) fn get_clause_type(
@@ -1157,6 +1165,7 @@ impl<'a> LoadState<'a> {
                 stream,
                 self.wam.machine_st.atom_tbl.clone(),
                 self.wam.machine_st.flags,
+                self.wam.machine_st.char_conversions.clone(),
                 listing_src,
             );
 
@@ -1210,6 +1219,7 @@ impl<'a> LoadState<'a> {
                 stream,
                 self.wam.machine_st.atom_tbl.clone(),
                 self.wam.machine_st.flags,
+                self.wam.machine_st.char_conversions.clone(),
                 listing_src,
             );
 
@@ -626,7 +626,7 @@ impl<'a> Drop for LoadState<'a> {
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-pub(crate) enum CompilationTarget {
+pub enum CompilationTarget {
     Module(ClauseName),
     User,
 }
@@ -779,7 +779,7 @@ impl<'a, TS: TermStream> Loader<'a, TS> {
                 self.non_counted_bt_preds.insert((name, arity));
             }
             Declaration::Op(op_decl) => {
-                self.load_state.add_op_decl(&op_decl);
+                self.load_state.add_op_decl(&op_decl)?;
             }
             Declaration::UseModule(module_src) => {
                 self.load_state.use_module(module_src)?;
@@ -794,87 +794,7 @@ impl<'a, TS: TermStream> Loader<'a, TS> {
 
     pub(super) fn read_term_from_heap(&self, heap_term_loc: RegType) -> Result<Term, SessionError> {
         let machine_st = &self.load_state.wam.machine_st;
-        let term_addr = machine_st[heap_term_loc];
-
-        if machine_st.is_cyclic_term(term_addr) {
-            return Err(SessionError::from(CompilationError::CannotParseCyclicTerm));
-        }
-
-        let mut term_stack = vec![];
-
-        for addr in machine_st.post_order_iter(term_addr) {
-            match machine_st.heap.index_addr(&addr).as_ref() {
-                HeapCellValue::Addr(Addr::Lis(_)) | HeapCellValue::Addr(Addr::PStrLocation(..)) => {
-                    let tail = term_stack.pop().unwrap();
-                    let head = term_stack.pop().unwrap();
-
-                    term_stack.push(Term::Cons(Cell::default(), Box::new(head), Box::new(tail)));
-                }
-                HeapCellValue::Addr(addr) => {
-                    if let Some(r) = addr.as_var() {
-                        let offset_string = match r {
-                            Ref::HeapCell(h) | Ref::AttrVar(h) => format!("_{}", h),
-                            Ref::StackCell(fr, sc) => format!("_s_{}_{}", fr, sc),
-                        };
-
-                        term_stack.push(Term::Var(Cell::default(), Rc::new(offset_string)));
-                    } else {
-                        match addr.as_constant_index(machine_st) {
-                            Some(constant) => {
-                                term_stack.push(Term::Constant(Cell::default(), constant));
-                            }
-                            None => {
-                                return Err(SessionError::from(CompilationError::UnreadableTerm));
-                            }
-                        }
-                    }
-                }
-                HeapCellValue::Atom(ref name, ref shared_op_desc) => {
-                    term_stack.push(Term::Constant(
-                        Cell::default(),
-                        Constant::Atom(name.clone(), shared_op_desc.clone()),
-                    ));
-                }
-                HeapCellValue::Integer(ref integer) => {
-                    term_stack.push(Term::Constant(
-                        Cell::default(),
-                        Constant::Integer(integer.clone()),
-                    ));
-                }
-                HeapCellValue::NamedStr(arity, ref name, ref shared_op_desc) => {
-                    let subterms = term_stack
-                        .drain(term_stack.len() - arity..)
-                        .map(Box::new)
-                        .collect();
-
-                    term_stack.push(Term::Clause(
-                        Cell::default(),
-                        name.clone(),
-                        subterms,
-                        shared_op_desc.clone(),
-                    ));
-                }
-                HeapCellValue::PartialString(..) => {
-                    let string = machine_st.heap_pstr_iter(addr).to_string();
-                    term_stack.push(Term::Constant(
-                        Cell::default(),
-                        Constant::String(Rc::new(string)),
-                    ));
-                }
-                HeapCellValue::Rational(ref rational) => {
-                    term_stack.push(Term::Constant(
-                        Cell::default(),
-                        Constant::Rational(rational.clone()),
-                    ));
-                }
-                _ => {
-                    return Err(SessionError::from(CompilationError::UnreadableTerm));
-                }
-            }
-        }
-
-        debug_assert!(term_stack.len() == 1);
-        Ok(term_stack.pop().unwrap())
+        machine_st.read_term_from_heap(machine_st[heap_term_loc])
     }
 
     fn extract_module_export_list_from_heap(
@@ -1104,7 +1024,7 @@ impl<'a, TS: TermStream> Loader<'a, TS> {
         }
     }
 
-    fn add_discontiguous_predicate(
+    pub(super) fn add_discontiguous_predicate(
         &mut self,
         compilation_target: CompilationTarget,
         name: ClauseName,
@@ -2303,3 +2223,87 @@ pub(super) fn load_module(
     )
     .unwrap();
 }
+
+impl MachineState {
+    pub(super) fn read_term_from_heap(&self, term_addr: Addr) -> Result<Term, SessionError> {
+        if self.is_cyclic_term(term_addr) {
+            return Err(SessionError::from(CompilationError::CannotParseCyclicTerm));
+        }
+
+        let mut term_stack = vec![];
+
+        for addr in self.post_order_iter(term_addr) {
+            match self.heap.index_addr(&addr).as_ref() {
+                HeapCellValue::Addr(Addr::Lis(_)) | HeapCellValue::Addr(Addr::PStrLocation(..)) => {
+                    let tail = term_stack.pop().unwrap();
+                    let head = term_stack.pop().unwrap();
+
+                    term_stack.push(Term::Cons(Cell::default(), Box::new(head), Box::new(tail)));
+                }
+                HeapCellValue::Addr(addr) => {
+                    if let Some(r) = addr.as_var() {
+                        let offset_string = match r {
+                            Ref::HeapCell(h) | Ref::AttrVar(h) => format!("_{}", h),
+                            Ref::StackCell(fr, sc) => format!("_s_{}_{}", fr, sc),
+                        };
+
+                        term_stack.push(Term::Var(Cell::default(), Rc::new(offset_string)));
+                    } else {
+                        match addr.as_constant_index(self) {
+                            Some(constant) => {
+                                term_stack.push(Term::Constant(Cell::default(), constant));
+                            }
+                            None => {
+                                return Err(SessionError::from(CompilationError::UnreadableTerm));
+                            }
+                        }
+                    }
+                }
+                HeapCellValue::Atom(ref name, ref shared_op_desc) => {
+                    term_stack.push(Term::Constant(
+                        Cell::default(),
+                        Constant::Atom(name.clone(), shared_op_desc.clone()),
+                    ));
+                }
+                HeapCellValue::Integer(ref integer) => {
+                    term_stack.push(Term::Constant(
+                        Cell::default(),
+                        Constant::Integer(integer.clone()),
+                    ));
+                }
+                HeapCellValue::NamedStr(arity, ref name, ref shared_op_desc) => {
+                    let subterms = term_stack
+                        .drain(term_stack.len() - arity..)
+                        .map(Box::new)
+                        .collect();
+
+                    term_stack.push(Term::Clause(
+                        Cell::default(),
+                        name.clone(),
+                        subterms,
+                        shared_op_desc.clone(),
+                    ));
+                }
+                HeapCellValue::PartialString(..) => {
+                    let string = self.heap_pstr_iter(addr).to_string();
+                    term_stack.push(Term::Constant(
+                        Cell::default(),
+                        Constant::String(Rc::new(string)),
+                    ));
+                }
+                HeapCellValue::Rational(ref rational) => {
+                    term_stack.push(Term::Constant(
+                        Cell::default(),
+                        Constant::Rational(rational.clone()),
+                    ));
+                }
+                _ => {
+                    return Err(SessionError::from(CompilationError::UnreadableTerm));
+                }
+            }
+        }
+
+        debug_assert!(term_stack.len() == 1);
+        Ok(term_stack.pop().unwrap())
+    }
+}
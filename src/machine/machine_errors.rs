@@ -365,6 +365,11 @@ impl MachineError {
                 "static_procedure",
                 functor!("query_cannot_be_defined_as_fact"),
             ),
+            SessionError::Timeout => MachineError {
+                stub: functor!("time_limit_exceeded"),
+                location: None,
+                from: ErrorProvenance::Received,
+            },
         }
     }
 
@@ -375,6 +380,10 @@ impl MachineError {
             return Self::arithmetic_error(h, err);
         }
 
+        if let CompilationError::ParserError(ParserError::Utf8Error(..)) = err {
+            return Self::encoding_error(err.line_and_col_num());
+        }
+
         let location = err.line_and_col_num();
         let stub = err.as_functor(h);
 
@@ -387,6 +396,27 @@ impl MachineError {
         }
     }
 
+    // input that is not valid in the encoding of its stream.
+    pub(super) fn encoding_error(location: Option<(usize, usize)>) -> Self {
+        let stub = functor!("io_error", [atom("encoding"), atom("utf8")]);
+
+        MachineError {
+            stub,
+            location,
+            from: ErrorProvenance::Received,
+        }
+    }
+
+    pub(super) fn resource_error(resource: &'static str) -> Self {
+        let stub = functor!("resource_error", [atom(resource)]);
+
+        MachineError {
+            stub,
+            location: None,
+            from: ErrorProvenance::Received,
+        }
+    }
+
     pub(super) fn representation_error(flag: RepFlag) -> Self {
         let stub = functor!("representation_error", [atom(flag.as_str())]);
 
@@ -415,7 +445,7 @@ impl MachineError {
 }
 
 #[derive(Debug)]
-pub(crate) enum CompilationError {
+pub enum CompilationError {
     Arithmetic(ArithmeticError),
     ParserError(ParserError),
     // BadPendingByte,
@@ -495,6 +525,7 @@ impl CompilationError {
 pub(crate) enum Permission {
     Access,
     Create,
+    Execute,
     InputStream,
     Modify,
     Open,
@@ -508,6 +539,7 @@ impl Permission {
         match self {
             Permission::Access => "access",
             Permission::Create => "create",
+            Permission::Execute => "execute",
             Permission::InputStream => "input",
             Permission::Modify => "modify",
             Permission::Open => "open",
@@ -568,6 +600,7 @@ impl ValidType {
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum DomainErrorType {
     IOMode,
+    NotLessThanOne,
     NotLessThanZero,
     Order,
     SourceSink,
@@ -579,6 +612,7 @@ impl DomainErrorType {
     pub(crate) fn as_str(self) -> &'static str {
         match self {
             DomainErrorType::IOMode => "io_mode",
+            DomainErrorType::NotLessThanOne => "not_less_than_one",
             DomainErrorType::NotLessThanZero => "not_less_than_zero",
             DomainErrorType::Order => "order",
             DomainErrorType::SourceSink => "source_sink",
@@ -806,7 +840,7 @@ impl MachineState {
 }
 
 #[derive(Debug)]
-pub(crate) enum ExistenceError {
+pub enum ExistenceError {
     Module(ClauseName),
     ModuleSource(ModuleSource),
     Procedure(ClauseName, usize),
@@ -815,7 +849,7 @@ pub(crate) enum ExistenceError {
 }
 
 #[derive(Debug)]
-pub(crate) enum SessionError {
+pub enum SessionError {
     CompilationError(CompilationError),
     // CannotOverwriteBuiltIn(ClauseName),
     // CannotOverwriteImport(ClauseName),
@@ -827,6 +861,7 @@ pub(crate) enum SessionError {
     OpIsInfixAndPostFix(ClauseName),
     PredicateNotMultifileOrDiscontiguous(CompilationTarget, PredicateKey),
     QueryCannotBeDefinedAsFact,
+    Timeout,
 }
 
 #[derive(Debug)]
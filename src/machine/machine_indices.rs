@@ -26,6 +26,7 @@ use std::fmt;
 // use std::mem;
 use std::net::TcpListener;
 use std::ops::{Add, AddAssign, Deref, Sub, SubAssign};
+use std::path::PathBuf;
 use std::rc::Rc;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -56,7 +57,7 @@ pub(crate) enum TermOrderCategory {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub(crate) enum Addr {
+pub enum Addr {
     AttrVar(usize),
     Char(char),
     Con(usize),
@@ -298,6 +299,7 @@ pub(crate) enum TrailRef {
     AttrVarListLink(usize, usize),
     BlackboardEntry(usize),
     BlackboardOffset(usize, usize), // key atom heap location, key value heap location
+    OverwrittenCell(usize, usize),  // overwritten heap location, old value heap location
 }
 
 impl From<Ref> for TrailRef {
@@ -681,6 +683,8 @@ pub(crate) struct IndexStore {
     pub(super) extensible_predicates: ExtensiblePredicates,
     pub(super) local_extensible_predicates: LocalExtensiblePredicates,
     pub(super) global_variables: GlobalVarDir,
+    pub(super) library_paths: Vec<PathBuf>,
+    pub(super) embedded_libraries: IndexMap<String, &'static str>,
     pub(super) meta_predicates: MetaPredicateDir,
     pub(super) modules: ModuleDir,
     pub(super) op_dir: OpDir,
@@ -13,6 +13,8 @@ use crate::machine::machine_indices::*;
 use crate::machine::partial_string::HeapPStrIter;
 use crate::machine::stack::*;
 use crate::machine::streams::*;
+use crate::read::write_term_to_heap;
+use crate::rug::rand::RandState;
 use crate::rug::Integer;
 
 use downcast::{
@@ -22,11 +24,15 @@ use downcast::{
 use indexmap::IndexMap;
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
 use std::mem;
 use std::ops::{Index, IndexMut};
 use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Debug)]
 pub(crate) struct Ball {
@@ -275,7 +281,7 @@ pub enum FirstOrNext {
 }
 
 // #[derive(Debug)]
-pub(crate) struct MachineState {
+pub struct MachineState {
     pub(crate) atom_tbl: TabledData<Atom>,
     pub(super) s: HeapPtr,
     pub(super) p: CodePtr,
@@ -285,6 +291,7 @@ pub(crate) struct MachineState {
     pub(super) num_of_args: usize,
     pub(super) cp: LocalCodePtr,
     pub(super) attr_var_init: AttrVarInitializer,
+    pub(super) enumerate_arg_loc: usize,
     pub(super) fail: bool,
     pub(crate) heap: Heap,
     pub(super) mode: MachineMode,
@@ -299,11 +306,66 @@ pub(crate) struct MachineState {
     pub(super) interms: Vec<Number>, // intermediate numbers.
     pub(super) last_call: bool,
     pub(crate) flags: MachineFlags,
+    pub(crate) char_conversions: Rc<HashMap<char, char>>,
+    pub(crate) rng: RandState<'static>,
     pub(crate) cc: usize,
     pub(crate) global_clock: usize,
     pub(crate) dynamic_mode: FirstOrNext,
     pub(crate) unify_fn: fn(&mut MachineState, Addr, Addr),
     pub(crate) bind_fn: fn(&mut MachineState, Ref, Addr),
+    pub(crate) resource_limits: ResourceLimits,
+    pub(crate) clock: Option<Box<dyn Fn() -> f64>>,
+    pub(crate) foreign_rust_predicates: IndexMap<PredicateKey, ForeignRustPredicate>,
+    pub(crate) env_policy: EnvPolicy,
+    pub(crate) process_execution: bool,
+    pub(crate) abort: Arc<AtomicBool>,
+    pub(crate) aborted: bool,
+    pub(crate) deadline: Option<Instant>,
+    pub(crate) deadline_calls: usize,
+    pub(crate) timed_out: bool,
+    pub(crate) inference_limit: Option<u64>,
+    pub(crate) inferences: u64,
+    pub(crate) inference_limit_tripped: bool,
+}
+
+/// Bounds on the memory used by the queries of a [`Machine`]. Exceeding
+/// a bound throws `error(resource_error(Resource), Name/Arity)`, where
+/// `Resource` is one of `heap`, `stack` or `trail` and `Name/Arity` is
+/// the predicate being called. `None` leaves the resource unbounded.
+///
+/// [`Machine`]: crate::machine::Machine
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// The maximum number of heap cells.
+    pub max_heap_cells: Option<usize>,
+    /// The maximum depth of the environment stack.
+    pub max_stack_frames: Option<usize>,
+    /// The maximum number of trail entries.
+    pub max_trail: Option<usize>,
+}
+
+/// A predicate implemented in Rust. It is passed the dereferenced
+/// arguments of a call and fails the call by returning `false`.
+pub type ForeignRustPredicate = Box<dyn FnMut(&mut MachineState, &[Addr]) -> bool>;
+
+/// The environment variables visible to `library(os)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvPolicy {
+    /// Read and modify the environment of the process.
+    Inherit,
+    /// Hide the environment of the process. `getenv/2` fails and
+    /// `setenv/2` and `unsetenv/1` throw a permission error.
+    Deny,
+    /// Present the given variables instead of the environment of the
+    /// process. `setenv/2` and `unsetenv/1` modify only this map.
+    Allow(IndexMap<String, String>),
+}
+
+impl Default for EnvPolicy {
+    #[inline]
+    fn default() -> Self {
+        EnvPolicy::Inherit
+    }
 }
 
 impl fmt::Debug for MachineState {
@@ -332,9 +394,26 @@ impl fmt::Debug for MachineState {
             .field("interms", &self.interms)
             .field("last_call", &self.last_call)
             .field("flags", &self.flags)
+            .field("char_conversions", &self.char_conversions)
             .field("cc", &self.cc)
             .field("global_clock", &self.global_clock)
             .field("dynamic_mode", &self.dynamic_mode)
+            .field("resource_limits", &self.resource_limits)
+            .field("clock", &self.clock.as_ref().map(|_| "<clock>"))
+            .field(
+                "foreign_rust_predicates",
+                &self.foreign_rust_predicates.len(),
+            )
+            .field("env_policy", &self.env_policy)
+            .field("process_execution", &self.process_execution)
+            .field("abort", &self.abort)
+            .field("aborted", &self.aborted)
+            .field("deadline", &self.deadline)
+            .field("deadline_calls", &self.deadline_calls)
+            .field("timed_out", &self.timed_out)
+            .field("inference_limit", &self.inference_limit)
+            .field("inferences", &self.inferences)
+            .field("inference_limit_tripped", &self.inference_limit_tripped)
             .field(
                 "unify_fn",
                 if self.unify_fn as usize == MachineState::unify as usize {
@@ -362,6 +441,23 @@ impl fmt::Debug for MachineState {
 }
 
 impl MachineState {
+    /// Unifies `a1` with `a2`, returning `false` if they don't unify.
+    pub fn unify_addrs(&mut self, a1: Addr, a2: Addr) -> bool {
+        (self.unify_fn)(self, a1, a2);
+        !self.fail
+    }
+
+    /// Writes `term` to the heap and unifies it with `addr`.
+    pub fn unify_with_term(&mut self, addr: Addr, term: &Term) -> bool {
+        let h = write_term_to_heap(term, self).heap_loc;
+        self.unify_addrs(addr, Addr::HeapCell(h))
+    }
+
+    /// Reads the term at `addr`, or `None` if it is cyclic.
+    pub fn term_at(&self, addr: Addr) -> Option<Term> {
+        self.read_term_from_heap(addr).ok()
+    }
+
     pub(crate) fn read_term(&mut self, mut stream: Stream, indices: &mut IndexStore) -> CallResult {
         fn push_var_eq_functors<'a>(
             heap: &mut Heap,
@@ -475,7 +571,7 @@ impl MachineState {
                     return Ok((self.unify_fn)(self, var_names_offset, var_names_addr));
                 }
                 Err(err) => {
-                    if let ParserError::UnexpectedEOF = err {
+                    if let ParserError::UnexpectedEOF(..) = err {
                         self.eof_action(
                             self[temp_v!(2)],
                             &mut orig_stream,
@@ -509,6 +605,7 @@ impl MachineState {
         let numbervars = self.store(self.deref(self[temp_v!(4)]));
         let quoted = self.store(self.deref(self[temp_v!(5)]));
         let max_depth = self.store(self.deref(self[temp_v!(7)]));
+        let spacing = self.store(self.deref(self[temp_v!(8)]));
 
         let mut printer = HCPrinter::new(&self, op_dir, PrinterOutputter::new());
 
@@ -536,6 +633,14 @@ impl MachineState {
             }
         }
 
+        if let &Addr::Con(h) = &spacing {
+            if let HeapCellValue::Atom(ref name, _) = &self.heap[h] {
+                printer.spacing_next_argument = name.as_str() == "next_argument";
+            } else {
+                unreachable!()
+            }
+        }
+
         match Number::try_from((max_depth, &self.heap)) {
             Ok(Number::Fixnum(n)) => {
                 if let Ok(n) = usize::try_from(n) {
@@ -1025,6 +1130,18 @@ pub(crate) trait CallPolicy: Any + fmt::Debug {
                 return_from_clause!(machine_st.last_call, machine_st)
             }
             &BuiltInClauseType::Arg => {
+                if machine_st.is_arg_enumeration() {
+                    let p = dir_entry!(machine_st.enumerate_arg_loc);
+
+                    if machine_st.last_call {
+                        machine_st.execute_at_index(3, p);
+                    } else {
+                        machine_st.call_at_index(3, p);
+                    }
+
+                    return Ok(());
+                }
+
                 machine_st.try_arg()?;
                 return_from_clause!(machine_st.last_call, machine_st)
             }
@@ -1099,7 +1216,7 @@ pub(crate) trait CallPolicy: Any + fmt::Debug {
                         let addr = machine_st[temp_v!(2)];
                         (machine_st.unify_fn)(machine_st, addr, Addr::HeapCell(offset.heap_loc));
                     }
-                    Err(ParserError::UnexpectedEOF) => {
+                    Err(ParserError::UnexpectedEOF(..)) => {
                         let addr = machine_st[temp_v!(2)];
                         let eof = clause_name!("end_of_file".to_string(), machine_st.atom_tbl);
 
@@ -1460,6 +1577,7 @@ fn cut_body(machine_st: &mut MachineState, addr: &Addr) -> bool {
         &Addr::CutPoint(b0) | &Addr::Usize(b0) => {
             if b > b0 {
                 machine_st.b = b0;
+                machine_st.tidy_trail(b);
             }
         }
         _ => {
@@ -18,14 +18,27 @@ use crate::machine::partial_string::*;
 use crate::machine::stack::*;
 use crate::machine::streams::*;
 use crate::machine::INTERRUPT;
+use crate::rug::rand::RandState;
 use crate::rug::Integer;
 use ordered_float::*;
 
 use indexmap::{IndexMap, IndexSet};
 
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+
+// the number of calls granted to the handlers of an exceeded global
+// inference limit, e.g. to print the error.
+const INFERENCE_LIMIT_HANDLER_ALLOWANCE: u64 = 1000;
+
+// the number of calls made between two readings of the clock while a
+// deadline is set.
+const DEADLINE_CHECK_INTERVAL: usize = 4096;
 
 impl MachineState {
     pub(crate) fn new() -> Self {
@@ -39,6 +52,7 @@ impl MachineState {
             num_of_args: 0,
             cp: LocalCodePtr::default(),
             attr_var_init: AttrVarInitializer::new(0),
+            enumerate_arg_loc: 0,
             fail: false,
             heap: Heap::new(),
             mode: MachineMode::Write,
@@ -53,11 +67,26 @@ impl MachineState {
             interms: vec![Number::default(); 256],
             last_call: false,
             flags: MachineFlags::default(),
+            char_conversions: Rc::new(HashMap::new()),
+            rng: RandState::new(),
             cc: 0,
             global_clock: 0,
             dynamic_mode: FirstOrNext::First,
             unify_fn: MachineState::unify,
             bind_fn: MachineState::bind,
+            resource_limits: ResourceLimits::default(),
+            clock: None,
+            foreign_rust_predicates: IndexMap::new(),
+            env_policy: EnvPolicy::default(),
+            process_execution: false,
+            abort: Arc::new(AtomicBool::new(false)),
+            aborted: false,
+            deadline: None,
+            deadline_calls: 0,
+            timed_out: false,
+            inference_limit: None,
+            inferences: 0,
+            inference_limit_tripped: false,
         }
     }
 
@@ -682,7 +711,52 @@ impl MachineState {
                 self.trail.push(TrailRef::BlackboardEntry(key_h));
                 self.tr += 1;
             }
+            TrailRef::OverwrittenCell(h, old_value_h) => {
+                if h < self.hb {
+                    self.trail.push(TrailRef::OverwrittenCell(h, old_value_h));
+                    self.tr += 1;
+                }
+            }
+        }
+    }
+
+    // drops the trail entries made redundant by cutting back to the
+    // current choice point, cut_b being the newest choice point cut.
+    // only the entries resetting cells older than the current choice
+    // point can ever be unwound. the scan starts at the trail mark of
+    // cut_b rather than that of the current choice point, so entries
+    // kept by one cut aren't rescanned by every cut that follows.
+    pub(super) fn tidy_trail(&mut self, cut_b: usize) {
+        let b = self.b;
+
+        let h = if b == 0 {
+            0
+        } else {
+            self.stack.index_or_frame(b).prelude.h
+        };
+
+        let tr = self.stack.index_or_frame(cut_b).prelude.tr;
+        let mut kept = tr;
+
+        for i in tr..self.tr {
+            let keep = match self.trail[i] {
+                TrailRef::Ref(Ref::HeapCell(c))
+                | TrailRef::Ref(Ref::AttrVar(c))
+                | TrailRef::AttrVarHeapLink(c)
+                | TrailRef::AttrVarListLink(c, _)
+                | TrailRef::OverwrittenCell(c, _) => c < h,
+                TrailRef::Ref(Ref::StackCell(fr, _)) => fr < b,
+                TrailRef::BlackboardEntry(_) | TrailRef::BlackboardOffset(..) => true,
+            };
+
+            if keep {
+                self.trail[kept] = self.trail[i];
+                kept += 1;
+            }
         }
+
+        self.trail.truncate(kept);
+        self.tr = kept;
     }
 
     fn increment_s_ptr(&mut self, rhs: usize) {
@@ -755,6 +829,9 @@ impl MachineState {
                         None => unreachable!(),
                     }
                 }
+                TrailRef::OverwrittenCell(h, old_value_h) => {
+                    self.heap[h] = self.heap.clone(old_value_h);
+                }
             }
         }
     }
@@ -1022,6 +1099,18 @@ impl MachineState {
                 self.interms[t - 1] = try_or_fail!(self, self.bitwise_complement(n1));
                 self.p += 1;
             }
+            &ArithmeticInstruction::Msb(ref a1, t) => {
+                let n1 = try_or_fail!(self, self.get_number(a1));
+
+                self.interms[t - 1] = try_or_fail!(self, self.msb(n1));
+                self.p += 1;
+            }
+            &ArithmeticInstruction::Lsb(ref a1, t) => {
+                let n1 = try_or_fail!(self, self.get_number(a1));
+
+                self.interms[t - 1] = try_or_fail!(self, self.lsb(n1));
+                self.p += 1;
+            }
             &ArithmeticInstruction::Div(ref a1, ref a2, t) => {
                 let n1 = try_or_fail!(self, self.get_number(a1));
                 let n2 = try_or_fail!(self, self.get_number(a2));
@@ -1144,6 +1233,14 @@ impl MachineState {
                     Number::Float(OrderedFloat(try_or_fail!(self, self.atan2(n1, n2))));
                 self.p += 1;
             }
+            &ArithmeticInstruction::LogBase(ref a1, ref a2, t) => {
+                let n1 = try_or_fail!(self, self.get_number(a1));
+                let n2 = try_or_fail!(self, self.get_number(a2));
+
+                self.interms[t - 1] =
+                    Number::Float(OrderedFloat(try_or_fail!(self, self.log_base(n1, n2))));
+                self.p += 1;
+            }
             &ArithmeticInstruction::Float(ref a1, t) => {
                 let n1 = try_or_fail!(self, self.get_number(a1));
 
@@ -1820,6 +1917,20 @@ impl MachineState {
         fail
     }
 
+    // arg(-N, +Term, ?Arg) with a compound Term is handed to
+    // '$enumerate_arg'/3, which backtracks over the positions of Term.
+    pub(super) fn is_arg_enumeration(&self) -> bool {
+        match self.store(self.deref(self[temp_v!(1)])) {
+            Addr::HeapCell(_) | Addr::StackCell(..) => {
+                match self.store(self.deref(self[temp_v!(2)])) {
+                    Addr::Str(_) | Addr::Lis(_) | Addr::PStrLocation(..) => true,
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
     // arg(+N, +Term, ?Arg)
     pub(super) fn try_arg(&mut self) -> CallResult {
         let stub = MachineError::functor_stub(clause_name!("arg"), 3);
@@ -2394,6 +2505,25 @@ impl MachineState {
                     }
                 }
             }
+            &InlinedClauseType::IsCallable(r1) => {
+                let d = self.store(self.deref(self[r1]));
+
+                match d {
+                    Addr::Con(h) => {
+                        if let HeapCellValue::Atom(..) = &self.heap[h] {
+                            self.p += 1;
+                        } else {
+                            self.fail = true;
+                        }
+                    }
+                    Addr::Char(_)
+                    | Addr::EmptyList
+                    | Addr::Str(_)
+                    | Addr::Lis(_)
+                    | Addr::PStrLocation(..) => self.p += 1,
+                    _ => self.fail = true,
+                };
+            }
             &InlinedClauseType::IsCompound(r1) => {
                 let d = self.store(self.deref(self[r1]));
 
@@ -2413,19 +2543,9 @@ impl MachineState {
             &InlinedClauseType::IsNumber(r1) => match self.store(self.deref(self[r1])) {
                 Addr::Float(_) => self.p += 1,
                 d => match Number::try_from((d, &self.heap)) {
-                    Ok(Number::Fixnum(_)) => {
+                    Ok(Number::Fixnum(_)) | Ok(Number::Integer(_)) | Ok(Number::Rational(_)) => {
                         self.p += 1;
                     }
-                    Ok(Number::Integer(_)) => {
-                        self.p += 1;
-                    }
-                    Ok(Number::Rational(n)) => {
-                        if n.denom() == &1 {
-                            self.p += 1;
-                        } else {
-                            self.fail = true;
-                        }
-                    }
                     _ => {
                         self.fail = true;
                     }
@@ -2608,7 +2728,6 @@ impl MachineState {
                     | Addr::Fixnum(_)
                     | Addr::Float(_)
                     | Addr::EmptyList
-                    | Addr::PStrLocation(..)
                     | Addr::Usize(_)
                         if arity == 0 =>
                     {
@@ -3020,12 +3139,24 @@ impl MachineState {
         self.p = CodePtr::BuiltInClause(ct, self.p.local());
     }
 
+    #[inline]
+    pub(super) fn and_frame_depth(&self) -> usize {
+        if self.e == 0 {
+            0
+        } else {
+            self.stack.index_and_frame(self.e).prelude.depth
+        }
+    }
+
     pub(super) fn allocate(&mut self, num_cells: usize) {
+        let depth = self.and_frame_depth() + 1;
+
         let e = self.stack.allocate_and_frame(num_cells);
         let and_frame = self.stack.index_and_frame_mut(e);
 
         and_frame.prelude.e = self.e;
         and_frame.prelude.cp = self.cp;
+        and_frame.prelude.depth = depth;
 
         self.e = e;
         self.p += 1;
@@ -3053,6 +3184,36 @@ impl MachineState {
         self.throw_exception(err);
     }
 
+    // the stack is checked before the frame of the called predicate
+    // is allocated, so that the error can name the predicate.
+    fn exceeded_resource_limit(&self) -> Option<&'static str> {
+        let limits = &self.resource_limits;
+
+        if limits
+            .max_heap_cells
+            .map_or(false, |max| self.heap.h() > max)
+        {
+            Some("heap")
+        } else if limits
+            .max_stack_frames
+            .map_or(false, |max| self.and_frame_depth() >= max)
+        {
+            Some("stack")
+        } else if limits.max_trail.map_or(false, |max| self.trail.len() > max) {
+            Some("trail")
+        } else {
+            None
+        }
+    }
+
+    fn throw_resource_error(&mut self, resource: &'static str, name: ClauseName, arity: usize) {
+        let err = MachineError::resource_error(resource);
+        let src = MachineError::functor_stub(name, arity);
+        let err = self.error_form(err, src);
+
+        self.throw_exception(err);
+    }
+
     fn handle_call_clause(
         &mut self,
         indices: &mut IndexStore,
@@ -3083,6 +3244,51 @@ impl MachineState {
             Err(_) => unreachable!(),
         }
 
+        if self.abort.swap(false, std::sync::atomic::Ordering::Relaxed) {
+            self.aborted = true;
+            self.throw_exception(functor!("$aborted"));
+            return;
+        }
+
+        if let Some(deadline) = self.deadline {
+            // reading the clock is costly next to a call, so the
+            // deadline is only checked every so often. once it has
+            // passed, every call throws.
+            self.deadline_calls = self.deadline_calls.wrapping_add(1);
+
+            if self.timed_out
+                || (self.deadline_calls % DEADLINE_CHECK_INTERVAL == 0
+                    && Instant::now() >= deadline)
+            {
+                self.timed_out = true;
+                self.throw_exception(functor!("time_limit_exceeded"));
+                return;
+            }
+        }
+
+        if let Some(resource) = self.exceeded_resource_limit() {
+            self.throw_resource_error(resource, ct.name(), arity);
+            return;
+        }
+
+        if let Some(inference_limit) = self.inference_limit {
+            // once the budget is spent, the handlers of the exception
+            // are allowed a few more calls, and no more.
+            let budget = if self.inference_limit_tripped {
+                inference_limit.saturating_add(INFERENCE_LIMIT_HANDLER_ALLOWANCE)
+            } else {
+                inference_limit
+            };
+
+            if self.inferences >= budget {
+                self.inference_limit_tripped = true;
+                self.throw_resource_error("inference_limit", ct.name(), arity);
+                return;
+            }
+
+            self.inferences += 1;
+        }
+
         let mut default_call_policy: Box<dyn CallPolicy> = Box::new(DefaultCallPolicy {});
 
         let call_policy = if use_default_cp {
@@ -3551,6 +3757,7 @@ impl MachineState {
 
                 if b > b0 {
                     self.b = b0;
+                    self.tidy_trail(b);
 
                     if b > self.e {
                         self.stack.truncate(b);
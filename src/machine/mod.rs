@@ -11,6 +11,7 @@ use crate::machine::heap::*;
 use crate::machine::loader::*;
 use crate::machine::term_stream::{LiveTermStream, LoadStatePayload, TermStream};
 use crate::read::*;
+use crate::rug::Integer;
 
 mod attributed_variables;
 pub(super) mod code_repo;
@@ -37,21 +38,37 @@ mod arithmetic_ops;
 mod machine_state_impl;
 mod system_calls;
 
+pub use crate::forms::OpDecl;
 use crate::machine::code_repo::*;
 use crate::machine::compile::*;
+pub use crate::machine::machine_errors::SessionError;
 use crate::machine::machine_errors::*;
+pub use crate::machine::machine_indices::Addr;
 use crate::machine::machine_indices::*;
 use crate::machine::machine_state::*;
+pub use crate::machine::machine_state::{
+    EnvPolicy, ForeignRustPredicate, MachineState, ResourceLimits,
+};
 pub use crate::machine::streams::Stream;
+pub use prolog_parser::ast::{Constant, Term};
 
 use indexmap::IndexMap;
 
+use std::collections::HashMap;
+
 //use std::convert::TryFrom;
 use prolog_parser::ast::ClauseName;
+use prolog_parser::parser::get_op_desc;
+use std::cell::Cell;
+use std::fmt;
 use std::fs::File;
+use std::io::Write;
 use std::mem;
 use std::path::PathBuf;
+use std::rc::Rc;
 use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub(crate) struct MachinePolicies {
@@ -106,6 +123,171 @@ impl LoadContext {
     }
 }
 
+/// The exception thrown while loading text passed to
+/// [`Machine::consult_incremental`].
+#[derive(Debug, Clone)]
+pub struct LoadError(pub Term);
+
+/// A warning collected by [`Machine::consult_string`]. Outside of it,
+/// warnings are printed to standard output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadWarning {
+    /// The clauses of `name/arity` replaced those loaded before.
+    Overwriting(String, usize),
+    /// The multifile predicate `module:name/arity` was redefined by
+    /// code that did not declare it multifile.
+    OverwritingMultifile(String, String, usize),
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadWarning::Overwriting(name, arity) => write!(f, "overwriting {}/{}", name, arity),
+            LoadWarning::OverwritingMultifile(module, name, arity) => write!(
+                f,
+                "overwriting multifile predicate {}:{}/{} because it was not \
+                 locally declared multifile.",
+                module, name, arity
+            ),
+        }
+    }
+}
+
+/// The answers to a query run by [`Machine::run_query_iter`], found
+/// one at a time as the iterator is advanced. Each answer maps the
+/// names of the query's variables to their bindings. An answer binding
+/// a variable to a cyclic term is replaced by the error `cyclic_term`,
+/// and an exception thrown by the query ends the answers with its
+/// ball. Dropping the iterator before the answers run out discards
+/// the alternatives left.
+#[derive(Debug)]
+pub struct QuerySolutions<'a> {
+    machine: &'a mut Machine,
+    query: Option<String>,
+    h: usize,
+    tr: usize,
+    b: usize,
+    block: usize,
+    done: bool,
+}
+
+impl<'a> QuerySolutions<'a> {
+    // abandons the query, backtracking to the last clause of
+    // '$run_query' if any alternatives are left.
+    fn finish(&mut self) {
+        self.done = true;
+
+        let machine_st = &mut self.machine.machine_st;
+
+        if machine_st.b > self.b {
+            let mut b = machine_st.b;
+
+            loop {
+                let prev_b = machine_st.stack.index_or_frame(b).prelude.b;
+
+                if prev_b <= self.b {
+                    break;
+                }
+
+                b = prev_b;
+            }
+
+            machine_st.b = b;
+            machine_st.backtrack();
+
+            self.machine.run_query();
+        }
+
+        self.machine.truncate_heap(self.h, self.tr);
+        self.machine.machine_st.block = self.block;
+    }
+}
+
+impl<'a> Iterator for QuerySolutions<'a> {
+    type Item = Result<HashMap<String, Term>, Term>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.query.take() {
+            Some(query) => self.machine.start_query(query),
+            None if self.machine.machine_st.b > self.b => {
+                self.machine.machine_st.backtrack();
+                self.machine.run_query();
+            }
+            None => {
+                self.finish();
+                return None;
+            }
+        }
+
+        let h = self.h;
+        let result = self
+            .machine
+            .machine_st
+            .store(self.machine.machine_st.deref(Addr::HeapCell(h + 1)));
+
+        match result {
+            Addr::Str(s) => {
+                let ball = self.machine.ball_at(s + 1);
+                self.finish();
+
+                ball.map(Err)
+            }
+            result if result.is_ref() => {
+                // the alternatives have run out, unless an exception
+                // escaped the handler of '$run_query'.
+                let ball = self.machine.ball_at(h + 1);
+                self.finish();
+
+                ball.map(Err)
+            }
+            _ => Some(self.machine.solution_at(Addr::HeapCell(h))),
+        }
+    }
+}
+
+impl<'a> Drop for QuerySolutions<'a> {
+    fn drop(&mut self) {
+        if !self.done && self.query.is_none() {
+            self.finish();
+        }
+    }
+}
+
+/// How a predicate listed by [`Machine::defined_predicates`] came to
+/// be visible in the `user` module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PredicateKind {
+    /// A static predicate defined in `user`.
+    Static,
+    /// A dynamic predicate of `user`.
+    Dynamic,
+    /// A predicate imported from the named module.
+    Module(String),
+}
+
+/// Aborts the queries of a [`Machine`] from another thread. See
+/// [`Machine::abort_handle`].
+#[derive(Debug, Clone)]
+pub struct AbortHandle(Arc<AtomicBool>);
+
+impl AbortHandle {
+    /// Stops the query running on the machine at its next call by
+    /// throwing the reserved ball `'$aborted'`. The recovery goals of
+    /// the `catch/3` calls catching it are run, after which it is
+    /// thrown again, so that the cleanup goals of the query run but
+    /// the query cannot go on. An abort requested while no query is
+    /// running is dropped. The machine remains usable for later
+    /// queries.
+    #[inline]
+    pub fn abort(&self) {
+        self.0.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
 #[derive(Debug)]
 pub struct Machine {
     pub(super) machine_st: MachineState,
@@ -117,6 +299,192 @@ pub struct Machine {
     pub(super) user_output: Stream,
     pub(super) user_error: Stream,
     pub(super) load_contexts: Vec<LoadContext>,
+    pub(super) pending_consult: String,
+    pub(super) chunked_load: bool,
+    pub(super) load_warnings: Option<Vec<LoadWarning>>,
+}
+
+/// Configures a [`Machine`] beyond what [`Machine::new`] allows.
+pub struct MachineBuilder {
+    user_input: Stream,
+    user_output: Stream,
+    user_error: Stream,
+    library_paths: Vec<PathBuf>,
+    embedded_libraries: IndexMap<String, &'static str>,
+    resource_limits: ResourceLimits,
+    rng_seed: Option<u64>,
+    clock: Option<Box<dyn Fn() -> f64>>,
+    env_policy: EnvPolicy,
+    process_execution: bool,
+    initial_ops: Vec<OpDecl>,
+}
+
+impl fmt::Debug for MachineBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MachineBuilder")
+            .field("user_input", &self.user_input)
+            .field("user_output", &self.user_output)
+            .field("user_error", &self.user_error)
+            .field("library_paths", &self.library_paths)
+            .field("embedded_libraries", &self.embedded_libraries)
+            .field("resource_limits", &self.resource_limits)
+            .field("rng_seed", &self.rng_seed)
+            .field("clock", &self.clock.as_ref().map(|_| "<clock>"))
+            .field("env_policy", &self.env_policy)
+            .field("process_execution", &self.process_execution)
+            .field("initial_ops", &self.initial_ops)
+            .finish()
+    }
+}
+
+impl MachineBuilder {
+    #[inline]
+    pub fn new(user_input: Stream, user_output: Stream, user_error: Stream) -> Self {
+        MachineBuilder {
+            user_input,
+            user_output,
+            user_error,
+            library_paths: vec![],
+            embedded_libraries: IndexMap::new(),
+            resource_limits: ResourceLimits::default(),
+            rng_seed: None,
+            clock: None,
+            env_policy: EnvPolicy::default(),
+            process_execution: false,
+            initial_ops: vec![],
+        }
+    }
+
+    /// Sends the output of `user_output` to `writer`, such as a log
+    /// sink or a socket, in place of the stream given to
+    /// [`MachineBuilder::new`].
+    #[inline]
+    pub fn with_output_writer(mut self, writer: impl Write + 'static) -> Self {
+        self.user_output = Stream::from_writer(writer);
+        self
+    }
+
+    /// Sends the output of `user_error` to `writer`. See
+    /// [`MachineBuilder::with_output_writer`].
+    #[inline]
+    pub fn with_error_writer(mut self, writer: impl Write + 'static) -> Self {
+        self.user_error = Stream::from_writer(writer);
+        self
+    }
+
+    /// Directories searched, in order, for `library(Name)` imports
+    /// that don't name a library bundled with Scryer.
+    #[inline]
+    pub fn with_library_paths(mut self, library_paths: Vec<PathBuf>) -> Self {
+        self.library_paths = library_paths;
+        self
+    }
+
+    /// Makes `library(Name)` load from `source` rather than from a
+    /// file. Embedded libraries are consulted before the library paths.
+    #[inline]
+    pub fn with_embedded_library(mut self, name: &str, source: &'static str) -> Self {
+        self.embedded_libraries.insert(name.to_string(), source);
+        self
+    }
+
+    /// Bounds the heap, stack and trail used by queries. The limits
+    /// are checked as predicates are called, so they may be overrun
+    /// by the work done between two calls.
+    #[inline]
+    pub fn with_resource_limits(mut self, resource_limits: ResourceLimits) -> Self {
+        self.resource_limits = resource_limits;
+        self
+    }
+
+    /// Seeds the machine's random number generator, which
+    /// `library(random)` draws from, so that its results are
+    /// reproducible.
+    #[inline]
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng_seed = Some(seed);
+        self
+    }
+
+    /// Replaces the wall clock read by `get_time/1`. The clock returns
+    /// the number of seconds since the Unix epoch.
+    #[inline]
+    pub fn with_clock(mut self, clock: impl Fn() -> f64 + 'static) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Determines which environment variables `library(os)` sees. A
+    /// sandboxed host can hide the environment of the process, or
+    /// present a virtual one in its place.
+    #[inline]
+    pub fn with_env_policy(mut self, env_policy: EnvPolicy) -> Self {
+        self.env_policy = env_policy;
+        self
+    }
+
+    /// Allows `shell/2` to run commands. This is off by default, in
+    /// which case `shell/2` throws a permission error.
+    #[inline]
+    pub fn with_process_execution(mut self, process_execution: bool) -> Self {
+        self.process_execution = process_execution;
+        self
+    }
+
+    /// Declares operators in the manner of `op/3` before the standard
+    /// libraries are compiled. They are declared after the default
+    /// operators, so a priority of 0 removes one of those.
+    #[inline]
+    pub fn with_initial_ops(mut self, ops: Vec<OpDecl>) -> Self {
+        self.initial_ops.extend(ops);
+        self
+    }
+
+    /// Builds the machine, or returns the error of the first operator
+    /// given to [`MachineBuilder::with_initial_ops`] that can't be
+    /// declared, or of a standard library the operator table leaves
+    /// uncompilable.
+    pub fn build(self) -> Result<Machine, SessionError> {
+        let mut wam = Machine::bootstrap(
+            self.user_input,
+            self.user_output,
+            self.user_error,
+            self.initial_ops,
+        )?;
+        wam.indices.library_paths = self.library_paths;
+        wam.indices.embedded_libraries = self.embedded_libraries;
+        wam.machine_st.resource_limits = self.resource_limits;
+        wam.machine_st.clock = self.clock;
+        wam.machine_st.env_policy = self.env_policy;
+        wam.machine_st.process_execution = self.process_execution;
+
+        if let Some(seed) = self.rng_seed {
+            wam.machine_st.rng.seed(&Integer::from(seed));
+        }
+
+        Ok(wam)
+    }
+}
+
+// error(representation_error(term), _), reported in place of a ball or
+// an answer containing a term, such as a stream, that has no Term form.
+fn unreadable_term_error() -> Term {
+    let representation_error = Term::Clause(
+        Cell::default(),
+        clause_name!("representation_error"),
+        vec![Box::new(Term::Constant(
+            Cell::default(),
+            Constant::Atom(clause_name!("term"), None),
+        ))],
+        None,
+    );
+
+    Term::Clause(
+        Cell::default(),
+        clause_name!("error"),
+        vec![Box::new(representation_error), Box::new(Term::AnonVar)],
+        None,
+    )
 }
 
 #[inline]
@@ -128,6 +496,14 @@ include!(concat!(env!("OUT_DIR"), "/libraries.rs"));
 
 impl Machine {
     fn run_module_predicate(&mut self, module_name: ClauseName, key: PredicateKey) {
+        // an abort requested while no query was running is dropped.
+        self.machine_st
+            .abort
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+        self.machine_st.aborted = false;
+        self.machine_st.timed_out = false;
+        self.machine_st.ball.reset();
+
         if let Some(module) = self.indices.modules.get(&module_name) {
             if let Some(ref code_index) = module.code_dir.get(&key) {
                 let p = code_index.local().unwrap();
@@ -154,7 +530,430 @@ impl Machine {
         self.run_module_predicate(clause_name!("loader"), (clause_name!("file_load"), 2));
     }
 
-    fn load_top_level(&mut self) {
+    /// Retracts every clause of the dynamic predicates of the `user`
+    /// module and removes the global variables set by user code. The
+    /// system's own global variables, whose names begin with `$`,
+    /// loaded modules and static predicates are left intact. The
+    /// budget of the global inference limit starts over.
+    pub fn reset_dynamic_db(&mut self) {
+        self.indices
+            .global_variables
+            .retain(|name, _| name.as_str().starts_with('$'));
+
+        self.machine_st.inferences = 0;
+        self.machine_st.inference_limit_tripped = false;
+
+        self.run_module_predicate(
+            clause_name!("builtins"),
+            (clause_name!("$reset_dynamic_db"), 0),
+        );
+    }
+
+    /// Runs `goal` once in the `user` module, as a directive would.
+    /// If the goal throws, the ball is returned as the error. A goal
+    /// that fails without throwing is reported as `failed(Goal)`, and
+    /// a ball with no [`Term`] form, such as a stream, as
+    /// `error(representation_error(term), _)`. The heap used by the
+    /// goal is released once it completes.
+    pub fn run_directive(&mut self, goal: &str) -> Result<(), Term> {
+        let goal = format!("{}\n.", goal);
+        let (h0, tr) = (self.machine_st.heap.h(), self.machine_st.tr);
+
+        self.machine_st[temp_v!(1)] = self.machine_st.heap.put_complete_string(&goal);
+
+        let h = self.machine_st.heap.h();
+
+        self.machine_st
+            .heap
+            .push(HeapCellValue::Addr(Addr::HeapCell(h)));
+        self.machine_st[temp_v!(2)] = Addr::HeapCell(h);
+
+        self.run_module_predicate(
+            clause_name!("builtins"),
+            (clause_name!("$run_directive"), 2),
+        );
+
+        let ball = self.ball_at(h);
+        self.truncate_heap(h0, tr);
+
+        match ball {
+            Some(ball) => Err(ball),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs `goal` as [`Machine::run_directive`] does, but abandons it
+    /// once `timeout` has elapsed, returning [`SessionError::Timeout`].
+    /// Otherwise the outcome of the goal is returned as
+    /// `run_directive` reports it. The clock is read at predicate
+    /// calls, once every few thousand of them, so a goal can overrun
+    /// `timeout` by the time those take, and a single long-running
+    /// builtin is never interrupted. Past the deadline, every call
+    /// throws `time_limit_exceeded`, so a goal that catches the error
+    /// is cut short all the same. The machine remains usable
+    /// afterwards.
+    pub fn run_directive_with_timeout(
+        &mut self,
+        goal: &str,
+        timeout: Duration,
+    ) -> Result<Result<(), Term>, SessionError> {
+        let deadline = Instant::now() + timeout;
+
+        self.machine_st.deadline = Some(deadline);
+        let result = self.run_directive(goal);
+        self.machine_st.deadline = None;
+
+        if self.machine_st.timed_out {
+            return Err(SessionError::Timeout);
+        }
+
+        Ok(result)
+    }
+
+    /// Loads the complete clauses of `chunk` into the `user` module.
+    /// Text following the last end token is kept and read together
+    /// with the next chunk, so a clause may be split across calls,
+    /// and the clauses of a predicate may be spread over several
+    /// chunks. [`Machine::finish_consult_incremental`] loads the text
+    /// left over once the last chunk is passed.
+    pub fn consult_incremental(&mut self, chunk: &str) -> Result<(), LoadError> {
+        self.pending_consult.push_str(chunk);
+
+        let len = complete_clauses_len(&self.pending_consult);
+
+        if len == 0 {
+            return Ok(());
+        }
+
+        let src: String = self.pending_consult.drain(..len).collect();
+        self.consult_chunk_of_text(src)
+    }
+
+    /// Loads the text passed to [`Machine::consult_incremental`] that
+    /// is still pending, such as a last clause not followed by layout
+    /// text. Text that does not end in a complete clause is reported
+    /// as a syntax error.
+    pub fn finish_consult_incremental(&mut self) -> Result<(), LoadError> {
+        let src = mem::take(&mut self.pending_consult);
+
+        if src.trim().is_empty() {
+            return Ok(());
+        }
+
+        self.consult_chunk_of_text(src + "\n")
+    }
+
+    fn consult_chunk_of_text(&mut self, src: String) -> Result<(), LoadError> {
+        self.chunked_load = true;
+        let result = self.consult_chunk(src);
+        self.chunked_load = false;
+
+        result
+    }
+
+    /// Loads `src` into the `user` module as a file would be, and
+    /// returns the warnings raised along the way rather than printing
+    /// them. Loading stops at the first error, which is returned in
+    /// place of the warnings.
+    pub fn consult_string(&mut self, src: &str) -> Result<Vec<LoadWarning>, LoadError> {
+        self.load_warnings = Some(vec![]);
+
+        let result = self.consult_chunk(src.to_string());
+        let warnings = self.load_warnings.take().unwrap_or_default();
+
+        result.map(|_| warnings)
+    }
+
+    fn consult_chunk(&mut self, src: String) -> Result<(), LoadError> {
+        let (h, tr) = (self.machine_st.heap.h(), self.machine_st.tr);
+
+        self.machine_st
+            .heap
+            .push(HeapCellValue::Addr(Addr::HeapCell(h)));
+
+        self.machine_st[temp_v!(1)] = Addr::Stream(
+            self.machine_st
+                .heap
+                .push(HeapCellValue::Stream(Stream::from(src))),
+        );
+        self.machine_st[temp_v!(2)] = Addr::HeapCell(h);
+
+        self.run_module_predicate(
+            clause_name!("builtins"),
+            (clause_name!("$consult_chunk"), 2),
+        );
+
+        let ball = self.ball_at(h);
+        self.truncate_heap(h, tr);
+
+        match ball {
+            Some(ball) => Err(LoadError(ball)),
+            None => Ok(()),
+        }
+    }
+
+    // discards the heap cells and trail entries of a query run from
+    // the API once its outcome is read. global variables may hold the
+    // addresses of discarded cells, so the values of nb_setval/2 are
+    // copied from their balls again when next fetched, and those of
+    // b_setval/2 are dropped, as backtracking out of the query would.
+    fn truncate_heap(&mut self, h: usize, tr: usize) {
+        for (_, value_addr) in self.indices.global_variables.values_mut() {
+            *value_addr = None;
+        }
+
+        self.machine_st.trail.truncate(tr);
+        self.machine_st.tr = tr;
+        self.machine_st.heap.truncate(h);
+    }
+
+    /// Runs `query` in the `user` module and returns an iterator over
+    /// its answers, reading the bindings straight off the heap rather
+    /// than through their printed form. The query is run up to its
+    /// next answer each time the iterator is advanced, so that it can
+    /// have infinitely many answers.
+    pub fn run_query_iter(&mut self, query: &str) -> QuerySolutions<'_> {
+        QuerySolutions {
+            query: Some(format!("{}\n.", query)),
+            h: self.machine_st.heap.h(),
+            tr: self.machine_st.tr,
+            b: self.machine_st.b,
+            block: self.machine_st.block,
+            done: false,
+            machine: self,
+        }
+    }
+
+    // runs '$run_query' up to the first answer of query. the variable
+    // names of the answer are bound to the heap variable at h, and
+    // its outcome to that at h + 1.
+    fn start_query(&mut self, query: String) {
+        let h = self.machine_st.heap.h();
+
+        self.machine_st
+            .heap
+            .push(HeapCellValue::Addr(Addr::HeapCell(h)));
+        self.machine_st
+            .heap
+            .push(HeapCellValue::Addr(Addr::HeapCell(h + 1)));
+
+        self.machine_st[temp_v!(1)] = Addr::Stream(
+            self.machine_st
+                .heap
+                .push(HeapCellValue::Stream(Stream::from(query))),
+        );
+        self.machine_st[temp_v!(2)] = Addr::HeapCell(h);
+        self.machine_st[temp_v!(3)] = Addr::HeapCell(h + 1);
+
+        self.run_module_predicate(clause_name!("builtins"), (clause_name!("$run_query"), 3));
+    }
+
+    // reads one answer of '$run_query', a list of Name = Value pairs.
+    fn solution_at(&self, addr: Addr) -> Result<HashMap<String, Term>, Term> {
+        let error = |name| Term::Constant(Cell::default(), Constant::Atom(name, None));
+
+        if self.machine_st.is_cyclic_term(addr) {
+            return Err(error(clause_name!("cyclic_term")));
+        }
+
+        let mut var_names = self
+            .machine_st
+            .read_term_from_heap(addr)
+            .map_err(|_| unreadable_term_error())?;
+
+        let mut solution = HashMap::new();
+
+        while let Term::Cons(_, head, tail) = var_names {
+            if let Term::Clause(_, _, mut terms, _) = *head {
+                let value = *terms.pop().unwrap();
+
+                if let Some(name) = terms.pop().and_then(|name| name.name()) {
+                    solution.insert(name.as_str().to_string(), value);
+                }
+            }
+
+            var_names = *tail;
+        }
+
+        Ok(solution)
+    }
+
+    // reads back the ball bound to the heap variable at h by one of
+    // the catching wrappers of builtins, if anything was thrown. A
+    // ball thrown by the catching machinery itself, as happens once
+    // the allowance of an exceeded inference limit is spent, escapes
+    // the wrapper and is read from the machine instead.
+    fn ball_at(&mut self, h: usize) -> Option<Term> {
+        let mut ball = self
+            .machine_st
+            .store(self.machine_st.deref(Addr::HeapCell(h)));
+
+        if ball.is_ref() {
+            if self.machine_st.ball.stub.h() == 0 {
+                return None;
+            }
+
+            let h = self.machine_st.heap.h();
+            let stub = self.machine_st.ball.copy_and_align(h);
+
+            self.machine_st.heap.extend(stub.into_iter());
+            ball = self.machine_st.heap[h].as_addr(h);
+        }
+
+        match self.machine_st.read_term_from_heap(ball) {
+            Ok(term) => Some(term),
+            Err(_) => Some(unreadable_term_error()),
+        }
+    }
+
+    /// Lists the name, arity and kind of the predicates visible in the
+    /// `user` module. The predicates of `builtins` and those with
+    /// hidden names are left out.
+    pub fn defined_predicates(&self) -> Vec<(String, usize, PredicateKind)> {
+        let mut predicates = vec![];
+
+        for (key, code_index) in self.indices.code_dir.iter() {
+            if code_index.is_undefined() || key.0.as_str().starts_with('$') {
+                continue;
+            }
+
+            let exporter = self.indices.modules.iter().find(|(_, module)| {
+                let exported = module
+                    .module_decl
+                    .exports
+                    .contains(&ModuleExport::PredicateKey(key.clone()));
+
+                exported
+                    && module
+                        .code_dir
+                        .get(key)
+                        .map_or(false, |idx| Rc::ptr_eq(&idx.0, &code_index.0))
+            });
+
+            let kind = match exporter {
+                Some((name, _))
+                    if name.as_str() == "builtins" || name.as_str().starts_with('$') =>
+                {
+                    continue;
+                }
+                Some((name, _)) => PredicateKind::Module(name.as_str().to_string()),
+                None => match code_index.get() {
+                    IndexPtr::DynamicIndex(_) | IndexPtr::DynamicUndefined => {
+                        PredicateKind::Dynamic
+                    }
+                    _ => PredicateKind::Static,
+                },
+            };
+
+            predicates.push((key.0.as_str().to_string(), key.1, kind));
+        }
+
+        predicates
+    }
+
+    /// Defines `name/arity` in the `user` module as a call to `f`,
+    /// which is passed the dereferenced arguments of the call.
+    /// Registering `name/arity` again replaces its closure.
+    pub fn register_foreign_rust_predicate(
+        &mut self,
+        name: &str,
+        arity: usize,
+        f: ForeignRustPredicate,
+    ) {
+        let key = (
+            clause_name!(name.to_string(), self.machine_st.atom_tbl),
+            arity,
+        );
+        let (id, replaced) = self
+            .machine_st
+            .foreign_rust_predicates
+            .insert_full(key.clone(), f);
+
+        // the code calling a predicate registered before is kept, as
+        // it dispatches on the unchanged index of the closure.
+        if replaced.is_some() {
+            return;
+        }
+
+        let p = self.code_repo.code.len();
+
+        self.code_repo
+            .code
+            .push(Line::Control(ControlInstruction::CallClause(
+                ClauseType::System(SystemClauseType::Foreign(id)),
+                arity,
+                0,
+                true,
+                false,
+            )));
+
+        self.indices
+            .code_dir
+            .entry(key)
+            .or_insert_with(|| CodeIndex::new(IndexPtr::Undefined))
+            .set(IndexPtr::Index(p));
+    }
+
+    /// Bounds the number of predicate calls made by the machine from
+    /// now on, across all of its queries and the loading of files, to
+    /// `limit`. The call exceeding the budget throws
+    /// `error(resource_error(inference_limit), _)` instead. Its handlers
+    /// are allowed a small number of further calls, past which every
+    /// call throws the error again until the budget is started over by
+    /// this method or [`Machine::reset_dynamic_db`]. `None` lifts the
+    /// limit.
+    pub fn set_global_inference_limit(&mut self, limit: Option<u64>) {
+        self.machine_st.inference_limit = limit;
+        self.machine_st.inferences = 0;
+        self.machine_st.inference_limit_tripped = false;
+    }
+
+    /// A handle through which another thread can abort the queries of
+    /// the machine.
+    pub fn abort_handle(&self) -> AbortHandle {
+        AbortHandle(self.machine_st.abort.clone())
+    }
+
+    /// Whether the last query run by the machine, such as the loading
+    /// of a file, was stopped through an [`AbortHandle`].
+    pub fn was_aborted(&self) -> bool {
+        self.machine_st.aborted
+    }
+
+    /// The names of the modules loaded into the machine.
+    pub fn module_names(&self) -> Vec<String> {
+        self.indices
+            .modules
+            .keys()
+            .map(|name| name.as_str().to_string())
+            .collect()
+    }
+
+    /// The predicates exported by the module `name`, or `None` if no
+    /// such module is loaded.
+    pub fn module_exports(&self, name: &str) -> Option<Vec<(String, usize)>> {
+        let (_, module) = self
+            .indices
+            .modules
+            .iter()
+            .find(|(module_name, _)| module_name.as_str() == name)?;
+
+        let exports = module
+            .module_decl
+            .exports
+            .iter()
+            .filter_map(|export| match export {
+                ModuleExport::PredicateKey((name, arity)) => {
+                    Some((name.as_str().to_string(), *arity))
+                }
+                ModuleExport::OpDecl(_) => None,
+            })
+            .collect();
+
+        Some(exports)
+    }
+
+    fn load_top_level(&mut self) -> Result<(), SessionError> {
         let mut path_buf = current_dir();
         path_buf.push("toplevel.pl");
 
@@ -170,12 +969,16 @@ impl Machine {
                 &CompilationTarget::User,
                 toplevel,
             );
+
+            Ok(())
         } else {
-            unreachable!()
+            Err(SessionError::ExistenceError(ExistenceError::Module(
+                clause_name!("$toplevel"),
+            )))
         }
     }
 
-    fn load_special_forms(&mut self) {
+    fn load_special_forms(&mut self) -> Result<(), SessionError> {
         let mut path_buf = current_dir();
         path_buf.push("machine/attributed_variables.pl");
 
@@ -183,8 +986,7 @@ impl Machine {
             Stream::from(include_str!("attributed_variables.pl")),
             self,
             ListingSource::from_file_and_path(clause_name!("attributed_variables"), path_buf),
-        )
-        .unwrap();
+        )?;
 
         let mut path_buf = current_dir();
         path_buf.push("machine/project_attributes.pl");
@@ -193,14 +995,15 @@ impl Machine {
             Stream::from(include_str!("project_attributes.pl")),
             self,
             ListingSource::from_file_and_path(clause_name!("project_attributes"), path_buf),
-        )
-        .unwrap();
+        )?;
 
         if let Some(module) = self.indices.modules.get(&clause_name!("$atts")) {
             if let Some(code_index) = module.code_dir.get(&(clause_name!("driver"), 2)) {
                 self.machine_st.attr_var_init.verify_attrs_loc = code_index.local().unwrap();
             }
         }
+
+        Ok(())
     }
 
     pub fn run_top_level(&mut self) {
@@ -275,7 +1078,20 @@ impl Machine {
         }
     }
 
+    /// Builds a machine with the default operator table, under which
+    /// the standard libraries always compile. [`MachineBuilder::build`]
+    /// reports their failure to compile under other operators.
     pub fn new(user_input: Stream, user_output: Stream, user_error: Stream) -> Self {
+        Machine::bootstrap(user_input, user_output, user_error, vec![])
+            .expect("the standard libraries compile under the default operators")
+    }
+
+    fn bootstrap(
+        user_input: Stream,
+        user_output: Stream,
+        user_error: Stream,
+        initial_ops: Vec<OpDecl>,
+    ) -> Result<Self, SessionError> {
         use ref_thread_local::RefThreadLocal;
 
         let mut wam = Machine {
@@ -288,6 +1104,9 @@ impl Machine {
             user_output,
             user_error,
             load_contexts: vec![],
+            pending_consult: String::new(),
+            chunked_load: false,
+            load_warnings: None,
         };
 
         let mut lib_path = current_dir();
@@ -302,17 +1121,23 @@ impl Machine {
                 clause_name!("ops_and_meta_predicates.pl"),
                 lib_path.clone(),
             ),
-        )
-        .unwrap();
+        )?;
+
+        for op_decl in initial_ops {
+            wam.declare_initial_op(op_decl)?;
+        }
 
         bootstrapping_compile(
             Stream::from(LIBRARIES.borrow()["builtins"]),
             &mut wam,
             ListingSource::from_file_and_path(clause_name!("builtins.pl"), lib_path.clone()),
-        )
-        .unwrap();
+        )?;
 
         if let Some(builtins) = wam.indices.modules.get(&clause_name!("builtins")) {
+            if let Some(code_index) = builtins.code_dir.get(&(clause_name!("$enumerate_arg"), 3)) {
+                wam.machine_st.enumerate_arg_loc = code_index.local().unwrap();
+            }
+
             load_module(
                 &mut wam.indices.code_dir,
                 &mut wam.indices.op_dir,
@@ -330,8 +1155,7 @@ impl Machine {
             Stream::from(include_str!("../loader.pl")),
             &mut wam,
             ListingSource::from_file_and_path(clause_name!("loader.pl"), lib_path.clone()),
-        )
-        .unwrap();
+        )?;
 
         wam.configure_modules();
 
@@ -347,11 +1171,30 @@ impl Machine {
             unreachable!()
         }
 
-        wam.load_special_forms();
-        wam.load_top_level();
+        wam.load_special_forms()?;
+        wam.load_top_level()?;
         wam.configure_streams();
 
-        wam
+        Ok(wam)
+    }
+
+    fn declare_initial_op(&mut self, mut op_decl: OpDecl) -> Result<(), SessionError> {
+        match op_decl.spec {
+            XFX | XFY | YFX | XF | YF | FX | FY if op_decl.prec <= 1200 => {}
+            _ => return Err(SessionError::from(CompilationError::InconsistentEntry)),
+        }
+
+        if op_decl.prec == 0 {
+            op_decl.remove(&mut self.indices.op_dir);
+            Ok(())
+        } else {
+            let spec = get_op_desc(
+                op_decl.name.clone(),
+                &CompositeOpDir::new(&self.indices.op_dir, None),
+            );
+
+            op_decl.submit(spec, &mut self.indices.op_dir)
+        }
     }
 
     pub(crate) fn configure_streams(&mut self) {
@@ -604,6 +1447,14 @@ impl MachineState {
     fn backtrack(&mut self) {
         let b = self.b;
 
+        if b == 0 {
+            // there is nothing to backtrack to, as when an exception
+            // escapes every handler, so the query stops.
+            self.p = CodePtr::Local(LocalCodePtr::Halt);
+            self.fail = false;
+            return;
+        }
+
         self.b0 = self.stack.index_or_frame(b).prelude.b0;
         self.p = CodePtr::Local(self.stack.index_or_frame(b).prelude.bp);
 
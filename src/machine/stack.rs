@@ -58,6 +58,7 @@ pub(crate) struct AndFramePrelude {
     pub(crate) e: usize,
     pub(crate) cp: LocalCodePtr,
     pub(crate) interrupt_cp: LocalCodePtr,
+    pub(crate) depth: usize,
 }
 
 #[derive(Debug)]
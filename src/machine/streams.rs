@@ -61,6 +61,12 @@ pub(crate) enum EOFAction {
     Reset,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Encoding {
+    Octet,
+    Utf8,
+}
+
 #[derive(Debug, PartialEq)]
 pub(crate) enum AtEndOfStream {
     Not,
@@ -118,6 +124,7 @@ enum StreamInstance {
     Stdout,
     TcpStream(ClauseName, TcpStream),
     TlsStream(ClauseName, TlsStream<TcpStream>),
+    Writer(Box<dyn Write>),
 }
 
 impl StreamInstance {
@@ -152,6 +159,7 @@ impl StreamInstance {
             StreamInstance::OutputFile(..)
             | StreamInstance::Stderr
             | StreamInstance::Stdout
+            | StreamInstance::Writer(_)
             | StreamInstance::Null => Err(std::io::Error::new(
                 ErrorKind::PermissionDenied,
                 StreamError::ReadFromOutputStream,
@@ -196,6 +204,7 @@ impl fmt::Debug for StreamInstance {
             &StreamInstance::TlsStream(_, ref tls_stream) => {
                 write!(fmt, "TlsStream({:?})", tls_stream)
             }
+            &StreamInstance::Writer(_) => write!(fmt, "Writer(_)"),
         }
     }
 }
@@ -206,6 +215,58 @@ pub(crate) struct InnerStream {
     stream_inst: StreamInstance,
     past_end_of_stream: bool,
     lines_read: usize,
+    // the second byte of the UTF-8 encoding of an octet that didn't
+    // fit the buffer of the last read.
+    pending_octet: Option<u8>,
+}
+
+impl InnerStream {
+    // the bytes of a text stream with the octet encoding are read as
+    // the UTF-8 encoding of the characters of the same codes. bytes
+    // put back by the parser were decoded when first read.
+    fn read_octets(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut index = 0;
+
+        if let StreamInstance::PausedPrologStream(ref mut put_back, _) = self.stream_inst {
+            while index < buf.len() {
+                match put_back.pop() {
+                    Some(b) => {
+                        buf[index] = b;
+                        index += 1;
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        if index < buf.len() {
+            if let Some(b) = self.pending_octet.take() {
+                buf[index] = b;
+                index += 1;
+            }
+        }
+
+        if index == buf.len() {
+            return Ok(index);
+        }
+
+        let mut octets = vec![0u8; (buf.len() - index + 1) / 2];
+        let octets_read = self.stream_inst.read(&mut octets)?;
+        let mut utf8 = [0u8; 2];
+
+        for &octet in &octets[..octets_read] {
+            for &b in char::from(octet).encode_utf8(&mut utf8).as_bytes() {
+                if index < buf.len() {
+                    buf[index] = b;
+                    index += 1;
+                } else {
+                    self.pending_octet = Some(b);
+                }
+            }
+        }
+
+        Ok(index)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -219,6 +280,7 @@ impl WrappedStreamInstance {
             stream_inst,
             past_end_of_stream,
             lines_read: 0,
+            pending_octet: None,
         })))
     }
 }
@@ -293,6 +355,7 @@ pub(crate) struct StreamOptions {
     pub(crate) reposition: bool,
     pub(crate) alias: Option<ClauseName>,
     pub(crate) eof_action: EOFAction,
+    pub(crate) encoding: Encoding,
 }
 
 impl Default for StreamOptions {
@@ -303,6 +366,7 @@ impl Default for StreamOptions {
             reposition: false,
             alias: None,
             eof_action: EOFAction::EOFCode,
+            encoding: Encoding::Utf8,
         }
     }
 }
@@ -441,9 +505,11 @@ impl Stream {
             InnerStream {
                 past_end_of_stream,
                 stream_inst: StreamInstance::InputFile(_, ref mut file),
+                pending_octet,
                 ..
             } => {
                 file.seek(SeekFrom::Start(position)).unwrap();
+                *pending_octet = None;
 
                 if let Ok(metadata) = file.metadata() {
                     *past_end_of_stream = position > metadata.len();
@@ -526,6 +592,7 @@ impl Stream {
             StreamInstance::OutputFile(_, _, true) => "append",
             StreamInstance::Stderr
             | StreamInstance::Stdout
+            | StreamInstance::Writer(_)
             | StreamInstance::OutputFile(_, _, false) => "write",
             StreamInstance::Null => "",
         }
@@ -548,6 +615,13 @@ impl Stream {
         Stream::from_inst(StreamInstance::Stderr)
     }
 
+    /// A write-only stream passing its output on to `writer`, which is
+    /// flushed whenever the stream is.
+    #[inline]
+    pub fn from_writer(writer: impl Write + 'static) -> Self {
+        Stream::from_inst(StreamInstance::Writer(Box::new(writer)))
+    }
+
     #[inline]
     pub(crate) fn from_tcp_stream(address: ClauseName, tcp_stream: TcpStream) -> Self {
         tcp_stream.set_read_timeout(None).unwrap();
@@ -631,6 +705,7 @@ impl Stream {
             | StreamInstance::TcpStream(..)
             | StreamInstance::TlsStream(..)
             | StreamInstance::Bytes(_)
+            | StreamInstance::Writer(_)
             | StreamInstance::OutputFile(..) => true,
             _ => false,
         }
@@ -728,6 +803,15 @@ impl Stream {
     pub(crate) fn peek_char(&mut self) -> std::io::Result<char> {
         use unicode_reader::CodePoints;
 
+        let is_octet_text = {
+            let options = self.options();
+            options.encoding == Encoding::Octet && options.stream_type == StreamType::Text
+        };
+
+        if is_octet_text {
+            return self.peek_byte().map(char::from);
+        }
+
         match self.stream_inst.0.borrow_mut().stream_inst {
             StreamInstance::InputFile(_, ref mut file) => {
                 let c = {
@@ -1115,7 +1199,18 @@ impl MachineState {
 impl Read for Stream {
     #[inline]
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        let bytes_read = self.stream_inst.0.borrow_mut().stream_inst.read(buf)?;
+        let bytes_read = {
+            let mut inner = self.stream_inst.0.borrow_mut();
+
+            if inner.options.encoding == Encoding::Octet
+                && inner.options.stream_type == StreamType::Text
+            {
+                inner.read_octets(buf)?
+            } else {
+                inner.stream_inst.read(buf)?
+            }
+        };
+
         self.unpause_stream();
         Ok(bytes_read)
     }
@@ -1130,6 +1225,7 @@ impl Write for Stream {
             StreamInstance::Bytes(ref mut cursor) => cursor.write(buf),
             StreamInstance::Stdout => stdout().write(buf),
             StreamInstance::Stderr => stderr().write(buf),
+            StreamInstance::Writer(ref mut writer) => writer.write(buf),
             StreamInstance::PausedPrologStream(..)
             | StreamInstance::StaticStr(_)
             | StreamInstance::ReadlineStream(_)
@@ -1149,6 +1245,7 @@ impl Write for Stream {
             StreamInstance::Bytes(ref mut cursor) => cursor.flush(),
             StreamInstance::Stderr => stderr().flush(),
             StreamInstance::Stdout => stdout().flush(),
+            StreamInstance::Writer(ref mut writer) => writer.flush(),
             StreamInstance::PausedPrologStream(..)
             | StreamInstance::StaticStr(_)
             | StreamInstance::ReadlineStream(_)
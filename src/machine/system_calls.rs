@@ -17,6 +17,7 @@ use crate::machine;
 use crate::machine::code_repo::CodeRepo;
 use crate::machine::code_walker::*;
 use crate::machine::copier::*;
+use crate::machine::loader::CompilationTarget;
 use crate::machine::machine_errors::*;
 use crate::machine::machine_indices::*;
 use crate::machine::machine_state::*;
@@ -37,6 +38,7 @@ use std::env;
 use std::fs;
 use std::io::{ErrorKind, Read, Write};
 use std::iter::{once, FromIterator};
+use std::mem;
 use std::net::{TcpListener, TcpStream};
 use std::num::NonZeroU32;
 use std::ops::Sub;
@@ -620,6 +622,7 @@ impl MachineState {
         };
 
         let mut parser = Parser::new(&mut stream, self.atom_tbl.clone(), self.machine_flags());
+        parser.set_char_conversions(self.char_conversions.clone());
 
         match parser.read_term(&CompositeOpDir::new(&indices.op_dir, None)) {
             Err(err) => {
@@ -669,12 +672,14 @@ impl MachineState {
 
                         let cp = self.heap.to_local_code_ptr(&p_functor).unwrap();
                         let prev_e = self.e;
+                        let depth = self.and_frame_depth() + 1;
 
                         let e = self.stack.allocate_and_frame(num_cells);
                         let and_frame = self.stack.index_and_frame_mut(e);
 
                         and_frame.prelude.e = prev_e;
                         and_frame.prelude.cp = return_p;
+                        and_frame.prelude.depth = depth;
 
                         self.p = CodePtr::Local(cp + 1);
 
@@ -1792,6 +1797,33 @@ impl MachineState {
                     }
                     _ => unreachable!(),
                 };
+
+                if let Addr::Str(s) = a2 {
+                    fn single_char(mut cs: impl Iterator<Item = char>, c: char) -> char {
+                        match (cs.next(), cs.next()) {
+                            (Some(c), None) => c,
+                            _ => c,
+                        }
+                    }
+
+                    let value = match &self.heap[s] {
+                        HeapCellValue::NamedStr(1, name, _) => match name.as_str() {
+                            "digit" => c.to_digit(10).map(|w| Addr::Fixnum(w as isize)),
+                            "to_lower" => Some(Addr::Char(single_char(c.to_lowercase(), c))),
+                            "to_upper" => Some(Addr::Char(single_char(c.to_uppercase(), c))),
+                            _ => unreachable!(),
+                        },
+                        _ => unreachable!(),
+                    };
+
+                    match value {
+                        Some(value) => (self.unify_fn)(self, value, Addr::HeapCell(s + 1)),
+                        None => self.fail = true,
+                    }
+
+                    return return_from_clause!(self.last_call, self);
+                }
+
                 let chars = match a2 {
                     Addr::Con(h) if self.heap.atom_at(h) => {
                         if let HeapCellValue::Atom(name, _) = &self.heap[h] {
@@ -1827,7 +1859,7 @@ impl MachineState {
                 method_check!(is_alphanumeric, "alphanumeric");
                 macro_check!(alpha_numeric_char, "alnum");
                 method_check!(is_ascii, "ascii");
-                method_check!(is_ascii_punctuation, "ascii_ponctuaction");
+                method_check!(is_ascii_punctuation, "ascii_punctuation");
                 method_check!(is_ascii_graphic, "ascii_graphic");
                 // macro_check!(backslash_char, "backslash");
                 // macro_check!(back_quote_char, "back_quote");
@@ -1857,11 +1889,12 @@ impl MachineState {
                 // macro_check!(single_quote_char, "single_quote");
                 // macro_check!(small_letter_char, "lower");
                 macro_check!(solo_char, "solo");
-                // macro_check!(space_char, "space");
+                method_check!(is_whitespace, "space");
                 macro_check!(symbolic_hexadecimal_char, "symbolic_hexadecimal");
                 macro_check!(symbolic_control_char, "symbolic_control");
                 method_check!(is_uppercase, "upper");
                 // macro_check!(variable_indicator_char, "variable_indicator");
+                macro_check!(white_char, "white");
                 method_check!(is_whitespace, "whitespace");
             }
             &SystemClauseType::CheckCutPoint => {
@@ -2600,6 +2633,23 @@ impl MachineState {
                     return Ok(());
                 }
             }
+            &SystemClauseType::Foreign(id) => {
+                let mut predicates = mem::take(&mut self.foreign_rust_predicates);
+
+                if let Some((&(_, arity), f)) = predicates.get_index_mut(id) {
+                    let args: Vec<_> = (1..=arity)
+                        .map(|i| self.store(self.deref(self[temp_v!(i)])))
+                        .collect();
+
+                    if !f(self, &args) {
+                        self.fail = true;
+                    }
+                } else {
+                    self.fail = true;
+                }
+
+                self.foreign_rust_predicates = predicates;
+            }
             &SystemClauseType::FlushOutput => {
                 let mut stream = self.get_stream_or_alias(
                     self[temp_v!(1)],
@@ -2649,6 +2699,30 @@ impl MachineState {
 
                 (self.unify_fn)(self, Addr::Char(c), a1);
             }
+            &SystemClauseType::ClauseLocations => {
+                let key = self.read_predicate_key(self[temp_v!(1)], self[temp_v!(2)]);
+                let module_name = atom_from!(self, self.store(self.deref(self[temp_v!(3)])));
+
+                let compilation_target = match module_name.as_str() {
+                    "user" => CompilationTarget::User,
+                    _ => CompilationTarget::Module(module_name),
+                };
+
+                let locs: Vec<Addr> =
+                    match indices.get_predicate_skeleton(&compilation_target, &key) {
+                        Some(skeleton) => skeleton
+                            .clauses
+                            .iter()
+                            .map(|clause_index_info| {
+                                Addr::Fixnum(clause_index_info.clause_start as isize)
+                            })
+                            .collect(),
+                        None => vec![],
+                    };
+
+                let locs = Addr::HeapCell(self.heap.to_list(locs.into_iter()));
+                (self.unify_fn)(self, locs, self[temp_v!(4)]);
+            }
             &SystemClauseType::HeadIsDynamic => {
                 let module_name = atom_from!(self, self.store(self.deref(self[temp_v!(1)])));
 
@@ -3072,12 +3146,7 @@ impl MachineState {
                 }
             }
             &SystemClauseType::Maybe => {
-                let result = {
-                    let mut rand = RANDOM_STATE.borrow_mut();
-                    rand.bits(1) == 0
-                };
-
-                self.fail = result;
+                self.fail = self.rng.bits(1) == 0;
             }
             &SystemClauseType::CpuNow => {
                 let a1 = self[temp_v!(1)];
@@ -3090,6 +3159,40 @@ impl MachineState {
                 let str = self.systemtime_to_timestamp(SystemTime::now());
                 (self.unify_fn)(self, self[temp_v!(1)], str);
             }
+            &SystemClauseType::GetTime => {
+                let secs = match &self.clock {
+                    Some(clock) => clock(),
+                    None => SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .map(|duration| duration.as_secs_f64())
+                        .unwrap_or(0.0),
+                };
+
+                let addr = self.heap.put_constant(Constant::Float(OrderedFloat(secs)));
+                (self.unify_fn)(self, self[temp_v!(1)], addr);
+            }
+            &SystemClauseType::TimestampToTime => {
+                let secs = self.store(self.deref(self[temp_v!(1)]));
+
+                let secs = match Number::try_from((secs, &self.heap)) {
+                    Ok(Number::Float(OrderedFloat(n))) => n,
+                    Ok(Number::Fixnum(n)) => n as f64,
+                    Ok(Number::Integer(n)) => n.to_f64(),
+                    _ => {
+                        self.fail = true;
+                        return Ok(());
+                    }
+                };
+
+                let system_time = if secs >= 0.0 {
+                    SystemTime::UNIX_EPOCH + Duration::from_secs_f64(secs)
+                } else {
+                    SystemTime::UNIX_EPOCH - Duration::from_secs_f64(-secs)
+                };
+
+                let str = self.systemtime_to_timestamp(system_time);
+                (self.unify_fn)(self, self[temp_v!(2)], str);
+            }
             &SystemClauseType::OpDeclaration => {
                 let priority = self[temp_v!(1)];
                 let specifier = self[temp_v!(2)];
@@ -3161,7 +3264,14 @@ impl MachineState {
                 let reposition = self[temp_v!(6)];
                 let stream_type = self[temp_v!(7)];
 
-                let options = self.to_stream_options(alias, eof_action, reposition, stream_type);
+                let mut options =
+                    self.to_stream_options(alias, eof_action, reposition, stream_type);
+
+                options.encoding =
+                    match atom_from!(self, self.store(self.deref(self[temp_v!(8)]))).as_str() {
+                        "octet" => Encoding::Octet,
+                        _ => Encoding::Utf8,
+                    };
 
                 let mut stream = match self.store(self.deref(self[temp_v!(1)])) {
                     Addr::Con(h) if self.heap.atom_at(h) => match &self.heap[h] {
@@ -3803,6 +3913,90 @@ impl MachineState {
                     self.fail = true;
                 }
             },
+            &SystemClauseType::GetCharConversionFlag => {
+                let value = if self.flags.char_conversion {
+                    clause_name!("on")
+                } else {
+                    clause_name!("off")
+                };
+
+                let value = self.heap.to_unifiable(HeapCellValue::Atom(value, None));
+                (self.unify_fn)(self, self[temp_v!(1)], value);
+            }
+            &SystemClauseType::SetCharConversionFlag => match self[temp_v!(1)] {
+                Addr::Con(h) if self.heap.atom_at(h) => {
+                    if let HeapCellValue::Atom(ref atom, _) = &self.heap[h] {
+                        self.flags.char_conversion = match atom.as_str() {
+                            "on" => true,
+                            "off" => false,
+                            _ => {
+                                self.fail = true;
+                                return Ok(());
+                            }
+                        };
+                    } else {
+                        unreachable!()
+                    }
+                }
+                _ => {
+                    self.fail = true;
+                }
+            },
+            &SystemClauseType::CharConversion => {
+                let mut chars = [' '; 2];
+
+                for (i, c) in chars.iter_mut().enumerate() {
+                    *c = match self.store(self.deref(self[temp_v!(i + 1)])) {
+                        Addr::Char(c) => c,
+                        Addr::Con(h) => match &self.heap[h] {
+                            HeapCellValue::Atom(ref atom, _) if atom.is_char() => {
+                                atom.as_str().chars().next().unwrap()
+                            }
+                            _ => {
+                                self.fail = true;
+                                return Ok(());
+                            }
+                        },
+                        _ => {
+                            self.fail = true;
+                            return Ok(());
+                        }
+                    };
+                }
+
+                let [from, to] = chars;
+
+                let conversions = Rc::make_mut(&mut self.char_conversions);
+
+                if from == to {
+                    conversions.remove(&from);
+                } else {
+                    conversions.insert(from, to);
+                }
+            }
+            &SystemClauseType::CharConversions => {
+                let mut conversions: Vec<(char, char)> = self
+                    .char_conversions
+                    .iter()
+                    .map(|(&c, &d)| (c, d))
+                    .collect();
+
+                conversions.sort();
+
+                let mut pairs = Vec::with_capacity(conversions.len());
+
+                for (from, to) in conversions {
+                    pairs.push(HeapCellValue::Addr(Addr::HeapCell(self.heap.h())));
+
+                    self.heap
+                        .push(HeapCellValue::NamedStr(2, clause_name!("-"), None));
+                    self.heap.push(HeapCellValue::Addr(Addr::Char(from)));
+                    self.heap.push(HeapCellValue::Addr(Addr::Char(to)));
+                }
+
+                let pairs = Addr::HeapCell(self.heap.to_list(pairs.into_iter()));
+                (self.unify_fn)(self, self[temp_v!(1)], pairs);
+            }
             &SystemClauseType::InferenceLevel => {
                 let a1 = self[temp_v!(1)];
                 let a2 = self.store(self.deref(self[temp_v!(2)]));
@@ -4054,6 +4248,13 @@ impl MachineState {
                     unreachable!()
                 }
             }
+            &SystemClauseType::OpenCharsStream => {
+                let chars = self.heap_pstr_iter(self[temp_v!(1)]).to_string();
+                let stream =
+                    Addr::Stream(self.heap.push(HeapCellValue::Stream(Stream::from(chars))));
+
+                (self.unify_fn)(self, stream, self[temp_v!(2)]);
+            }
             &SystemClauseType::ResetBlock => {
                 let addr = self.deref(self[temp_v!(1)]);
                 self.reset_block(addr);
@@ -4068,6 +4269,61 @@ impl MachineState {
                 self.heap.push(HeapCellValue::Addr(Addr::HeapCell(h)));
                 self[temp_v!(4)] = Addr::HeapCell(h);
             }
+            &SystemClauseType::SetArg => {
+                // setarg/3 and nb_setarg/3 have already checked that
+                // the index lies within the arity of the term.
+                let n = self.store(self.deref(self[temp_v!(1)]));
+
+                let n = match Number::try_from((n, &self.heap)) {
+                    Ok(Number::Fixnum(n)) => n as usize,
+                    Ok(Number::Integer(n)) => n.to_usize().unwrap(),
+                    _ => unreachable!(),
+                };
+
+                let arg_h = match self.store(self.deref(self[temp_v!(2)])) {
+                    Addr::Str(s) => s + n,
+                    Addr::Lis(l) => l + n - 1,
+                    _ => {
+                        // partial strings are stored packed, so their
+                        // characters can't be overwritten in place.
+                        self.fail = true;
+                        return Ok(());
+                    }
+                };
+
+                let value = match self.store(self.deref(self[temp_v!(3)])) {
+                    Addr::StackCell(fr, sc) => {
+                        let h = self.heap.h();
+
+                        self.heap.push(HeapCellValue::Addr(Addr::HeapCell(h)));
+                        self.bind(Ref::StackCell(fr, sc), Addr::HeapCell(h));
+
+                        HeapCellValue::Addr(Addr::HeapCell(h))
+                    }
+                    Addr::Con(h) => self.heap.clone(h),
+                    value => HeapCellValue::Addr(value),
+                };
+
+                let backtrackable = match self.store(self.deref(self[temp_v!(4)])) {
+                    Addr::Con(h) if self.heap.atom_at(h) => {
+                        if let HeapCellValue::Atom(ref atom, _) = &self.heap[h] {
+                            atom.as_str() == "true"
+                        } else {
+                            unreachable!()
+                        }
+                    }
+                    _ => unreachable!(),
+                };
+
+                if backtrackable {
+                    let old_value = self.heap.clone(arg_h);
+                    let old_value_h = self.heap.push(old_value);
+
+                    self.trail(TrailRef::OverwrittenCell(arg_h, old_value_h));
+                }
+
+                self.heap[arg_h] = value;
+            }
             &SystemClauseType::SetBall => {
                 self.set_ball();
             }
@@ -4084,8 +4340,7 @@ impl MachineState {
                     }
                 };
 
-                let mut rand = RANDOM_STATE.borrow_mut();
-                rand.seed(&seed);
+                self.rng.seed(&seed);
             }
             &SystemClauseType::SkipMaxList => {
                 if let Err(err) = self.skip_max_list() {
@@ -5344,12 +5599,19 @@ impl MachineState {
             }
             &SystemClauseType::GetEnv => {
                 let key = self.heap_pstr_iter(self[temp_v!(1)]).to_string();
-                match env::var(key) {
-                    Ok(value) => {
+
+                let value = match &self.env_policy {
+                    EnvPolicy::Inherit => env::var(key).ok(),
+                    EnvPolicy::Deny => None,
+                    EnvPolicy::Allow(vars) => vars.get(&key).cloned(),
+                };
+
+                match value {
+                    Some(value) => {
                         let cstr = self.heap.put_complete_string(&value);
                         (self.unify_fn)(self, self[temp_v!(2)], cstr);
                     }
-                    _ => {
+                    None => {
                         self.fail = true;
                         return Ok(());
                     }
@@ -5358,11 +5620,91 @@ impl MachineState {
             &SystemClauseType::SetEnv => {
                 let key = self.heap_pstr_iter(self[temp_v!(1)]).to_string();
                 let value = self.heap_pstr_iter(self[temp_v!(2)]).to_string();
-                env::set_var(key, value);
+
+                match &mut self.env_policy {
+                    EnvPolicy::Inherit => env::set_var(key, value),
+                    EnvPolicy::Deny => {
+                        self.fail = true;
+                        return Ok(());
+                    }
+                    EnvPolicy::Allow(vars) => {
+                        vars.insert(key, value);
+                    }
+                }
             }
             &SystemClauseType::UnsetEnv => {
                 let key = self.heap_pstr_iter(self[temp_v!(1)]).to_string();
-                env::remove_var(key);
+
+                match &mut self.env_policy {
+                    EnvPolicy::Inherit => env::remove_var(key),
+                    EnvPolicy::Deny => {
+                        self.fail = true;
+                        return Ok(());
+                    }
+                    EnvPolicy::Allow(vars) => {
+                        vars.shift_remove(&key);
+                    }
+                }
+            }
+            &SystemClauseType::Environ => {
+                let vars: Vec<(String, String)> = match &self.env_policy {
+                    EnvPolicy::Inherit => env::vars().collect(),
+                    EnvPolicy::Deny => vec![],
+                    EnvPolicy::Allow(vars) => vars
+                        .iter()
+                        .map(|(key, value)| (key.clone(), value.clone()))
+                        .collect(),
+                };
+
+                let mut pairs = Vec::with_capacity(vars.len());
+
+                for (key, value) in vars {
+                    let key = self.heap.put_complete_string(&key);
+                    let value = self.heap.put_complete_string(&value);
+
+                    pairs.push(HeapCellValue::Addr(Addr::HeapCell(self.heap.h())));
+
+                    self.heap
+                        .push(HeapCellValue::NamedStr(2, clause_name!("="), None));
+                    self.heap.push(HeapCellValue::Addr(key));
+                    self.heap.push(HeapCellValue::Addr(value));
+                }
+
+                let pairs = Addr::HeapCell(self.heap.to_list(pairs.into_iter()));
+                (self.unify_fn)(self, self[temp_v!(1)], pairs);
+            }
+            &SystemClauseType::Shell => {
+                if !self.process_execution {
+                    let stub = MachineError::functor_stub(clause_name!("shell"), 2);
+                    let err = MachineError::permission_error(
+                        self.heap.h(),
+                        Permission::Execute,
+                        "shell",
+                        self[temp_v!(1)],
+                    );
+
+                    return Err(self.error_form(err, stub));
+                }
+
+                let command = self.heap_pstr_iter(self[temp_v!(1)]).to_string();
+
+                let status = if cfg!(windows) {
+                    process::Command::new("cmd").arg("/C").arg(command).status()
+                } else {
+                    process::Command::new("sh").arg("-c").arg(command).status()
+                };
+
+                match status.ok().and_then(|status| status.code()) {
+                    Some(code) => {
+                        let code = Rc::new(Integer::from(code));
+                        let code = self.heap.put_constant(Constant::Integer(code));
+                        (self.unify_fn)(self, self[temp_v!(2)], code);
+                    }
+                    None => {
+                        self.fail = true;
+                        return Ok(());
+                    }
+                }
             }
             &SystemClauseType::PID => {
                 let a1 = self[temp_v!(1)];
@@ -5431,11 +5773,17 @@ impl MachineState {
 
                 use crate::LIBRARIES;
 
-                match LIBRARIES.borrow().get(library_name.as_str()) {
+                let library = LIBRARIES
+                    .borrow()
+                    .get(library_name.as_str())
+                    .or_else(|| indices.embedded_libraries.get(library_name.as_str()))
+                    .cloned();
+
+                match library {
                     Some(library) => {
                         let var_ref = Ref::HeapCell(
                             self.heap
-                                .push(HeapCellValue::Stream(Stream::from(*library))),
+                                .push(HeapCellValue::Stream(Stream::from(library))),
                         );
 
                         self.bind(var_ref, self[temp_v!(2)]);
@@ -5465,6 +5813,17 @@ impl MachineState {
                     }
                 }
             }
+            &SystemClauseType::LibraryPaths => {
+                let mut paths = vec![];
+
+                for path in &indices.library_paths {
+                    let path = clause_name!(path.to_string_lossy().into_owned(), self.atom_tbl);
+                    paths.push(self.heap.to_unifiable(HeapCellValue::Atom(path, None)));
+                }
+
+                let paths_list = Addr::HeapCell(self.heap.to_list(paths.into_iter()));
+                (self.unify_fn)(self, self[temp_v!(1)], paths_list);
+            }
             &SystemClauseType::DevourWhitespace => {
                 let stream = self.get_stream_or_alias(
                     self[temp_v!(1)],
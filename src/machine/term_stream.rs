@@ -6,8 +6,9 @@ use crate::machine::*;
 
 use indexmap::IndexSet;
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
+use std::rc::Rc;
 
 pub(crate) trait TermStream: Sized {
     type Evacuable;
@@ -30,9 +31,12 @@ impl<'a> BootstrappingTermStream<'a> {
         stream: &'a mut PrologStream,
         atom_tbl: TabledData<Atom>,
         flags: MachineFlags,
+        char_conversions: Rc<HashMap<char, char>>,
         listing_src: ListingSource,
     ) -> Self {
-        let parser = Parser::new(stream, atom_tbl, flags);
+        let mut parser = Parser::new(stream, atom_tbl, flags);
+        parser.set_char_conversions(char_conversions);
+
         Self {
             parser,
             listing_src,
@@ -225,6 +225,12 @@ macro_rules! is_integer {
     };
 }
 
+macro_rules! is_callable {
+    ($r:expr) => {
+        call_clause!(ClauseType::Inlined(InlinedClauseType::IsCallable($r)), 1, 0)
+    };
+}
+
 macro_rules! is_compound {
     ($r:expr) => {
         call_clause!(ClauseType::Inlined(InlinedClauseType::IsCompound($r)), 1, 0)
@@ -365,6 +371,8 @@ macro_rules! index_store {
             extensible_predicates: ExtensiblePredicates::new(),
             local_extensible_predicates: LocalExtensiblePredicates::new(),
             global_variables: GlobalVarDir::new(),
+            library_paths: vec![],
+            embedded_libraries: IndexMap::new(),
             meta_predicates: MetaPredicateDir::new(),
             modules: $modules,
             op_dir: $op_dir,
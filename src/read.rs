@@ -1,6 +1,7 @@
 use prolog_parser::ast::*;
 use prolog_parser::parser::*;
 use prolog_parser::tabled_rc::TabledData;
+use prolog_parser::{alpha_numeric_char, end_line_comment_char, graphic_token_char, layout_char};
 
 use crate::forms::*;
 use crate::iterators::*;
@@ -182,6 +183,7 @@ impl MachineState {
         let mut stream = parsing_stream(inner.clone())?;
         let mut parser = Parser::new(&mut stream, atom_tbl, self.flags);
 
+        parser.set_char_conversions(self.char_conversions.clone());
         parser.devour_whitespace()?;
 
         inner.add_lines_read(parser.num_lines_read());
@@ -206,6 +208,7 @@ impl MachineState {
             let prior_num_lines_read = inner.lines_read();
             let mut parser = Parser::new(&mut stream, atom_tbl, self.flags);
 
+            parser.set_char_conversions(self.char_conversions.clone());
             parser.add_lines_read(prior_num_lines_read);
 
             let term = parser.read_term(&CompositeOpDir::new(op_dir, None))?;
@@ -225,6 +228,88 @@ impl MachineState {
     }
 }
 
+/// Returns the length of the longest prefix of `src` made up of
+/// complete clauses, i.e. the offset just past the last end token
+/// followed by layout text or a comment. Quoted text, comments and
+/// `0'c` character codes are skipped over.
+pub(crate) fn complete_clauses_len(src: &str) -> usize {
+    let mut chars = src.char_indices().peekable();
+    let mut prev = ' ';
+    let mut prev_prev = ' ';
+    let mut len = 0;
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '%' => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+
+                prev_prev = prev;
+                prev = '\n';
+
+                continue;
+            }
+            '/' if !graphic_token_char!(prev) && matches!(chars.peek(), Some((_, '*'))) => {
+                let mut star = false;
+                chars.next();
+
+                for (_, c) in chars.by_ref() {
+                    if star && c == '/' {
+                        break;
+                    }
+
+                    star = c == '*';
+                }
+
+                prev_prev = prev;
+                prev = ' ';
+
+                continue;
+            }
+            '\'' if prev == '0' && !alpha_numeric_char!(prev_prev) => {
+                match chars.next() {
+                    Some((_, '\\')) => {
+                        chars.next();
+                    }
+                    Some((_, '\'')) if matches!(chars.peek(), Some((_, '\''))) => {
+                        chars.next();
+                    }
+                    _ => {}
+                }
+
+                prev_prev = prev;
+                prev = 'c';
+
+                continue;
+            }
+            '\'' | '"' | '`' => {
+                while let Some((_, q)) = chars.next() {
+                    if q == '\\' {
+                        chars.next();
+                    } else if q == c {
+                        break;
+                    }
+                }
+            }
+            '.' if !graphic_token_char!(prev) => match chars.peek() {
+                Some(&(_, c)) if layout_char!(c) || end_line_comment_char!(c) => {
+                    len = i + 1;
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        prev_prev = prev;
+        prev = c;
+    }
+
+    len
+}
+
 #[inline]
 pub(crate) fn write_term_to_heap(term: &Term, machine_st: &mut MachineState) -> TermWriteResult {
     let term_writer = TermWriter::new(machine_st);
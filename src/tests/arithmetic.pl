@@ -0,0 +1,45 @@
+:- module(tests_on_arithmetic, []).
+
+:- use_module(library(arithmetic)).
+
+test_divmod :-
+    divmod(17, 5, 3, 2),
+    divmod(-17, 5, -4, 3),
+    divmod(17, -5, -4, -3),
+    N is 2 ^ 100,
+    divmod(N, 3, Q, R),
+    Q * 3 + R =:= N,
+    catch((divmod(_, 5, _, _), false), error(instantiation_error, _), true),
+    catch((divmod(1.0, 5, _, _), false), error(type_error(integer, 1.0), _), true),
+    catch((divmod(1, 0, _, _), false), error(evaluation_error(zero_divisor), _), true).
+
+:- initialization(test_divmod).
+
+test_succ :-
+    succ(3, 4),
+    succ(3, Y), Y == 4,
+    succ(X, 4), X == 3,
+    succ(0, 1),
+    \+ succ(_, 0),
+    \+ succ(3, 5),
+    N is 2 ^ 100,
+    succ(N, M), M - N =:= 1,
+    succ(P, M), P == N,
+    catch((succ(_, _), false), error(instantiation_error, _), true),
+    catch((succ(-1, _), false), error(type_error(not_less_than_zero, -1), _), true),
+    catch((succ(_, -1), false), error(type_error(not_less_than_zero, -1), _), true),
+    catch((succ(3, -1), false), error(type_error(not_less_than_zero, -1), _), true),
+    catch((succ(a, _), false), error(type_error(integer, a), _), true).
+
+:- initialization(test_succ).
+
+test_gcd :-
+    X is gcd(48, 36), X == 12,
+    Y is gcd(-48, 36), Y == 12,
+    Z is gcd(0, 0), Z == 0,
+    N is 2 ^ 100,
+    G is gcd(N, 2 ^ 70 * 3), G =:= 2 ^ 70,
+    catch((_ is gcd(1.0, 2), false), error(type_error(integer, 1.0), _), true),
+    catch((_ is gcd(2, 1 rdiv 2), false), error(type_error(integer, _), _), true).
+
+:- initialization(test_gcd).
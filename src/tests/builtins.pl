@@ -1,5 +1,7 @@
 :- module(tests_on_builtins, []).
 
+:- use_module(library(between)).
+:- use_module(library(charsio)).
 :- use_module(library(lists)).
 :- use_module(library(iso_ext)).
 
@@ -29,12 +31,31 @@ test_queries_on_builtins :-
     arg(3, f(a,b,c,d), c),
     arg(4, f(a,b,c,d), d),
     catch(arg(_, f, _), error(instantiation_error, _), true),
+    findall(ArgN-ArgV, arg(ArgN, f(a,b,c), ArgV), [1-a, 2-b, 3-c]),
+    \+ arg(0, f(a), _),
+    findall(ArgN-ArgV, arg(ArgN, [x|y], ArgV), [1-x, 2-y]),
+    findall(ArgN, call(arg, ArgN, f(a,b), _), [1, 2]),
     \+ arg(1, f(arg, not_arg, not_arg), not_arg),
     arg(2, f(arg, not_arg, not_arg), not_arg),
     arg(3, f(arg, not_arg, not_arg), not_arg),
     functor(f(a,b,c), f, 3),
+    functor(T0, foo, 2),
+    T0 = foo(V1, V2),
+    var(V1), var(V2), V1 \== V2,
+    functor(T1, 5, 0),
+    T1 == 5,
+    functor(T2, 1.5, 0),
+    T2 == 1.5,
+    catch((functor(_, f(a), 0), false), error(type_error(atomic, f(a)), _), true),
+    catch((functor(_, [a], 0), false), error(type_error(atomic, [a]), _), true),
     catch(functor(_,"sdf",3),error(type_error(atom,[s,d,f]),_),true),
     f(1,2,3) =.. [f,1,2,3],
+    catch((_ =.. [foo|_], false), error(instantiation_error, _), true),
+    catch((_ =.. _, false), error(instantiation_error, _), true),
+    catch((_ =.. [foo(a), b], false), error(type_error(atom, foo(a)), _), true),
+    catch((_ =.. [foo(a)], false), error(type_error(atomic, foo(a)), _), true),
+    catch((_ =.. [], false), error(domain_error(non_empty_list, []), _), true),
+    catch((_ =.. [foo|bar], false), error(type_error(list, [foo|bar]), _), true),
     length([a,b,c], 3),
     \+ \+ copy_term([[[[X,Y],Y],X]],[[[[Z,V],V],Z]]),
     \+ ( X = g(X,Y), Y = f(X), copy_term(Y,g(Z)) ),
@@ -46,6 +67,13 @@ test_queries_on_builtins :-
     \+ float([1,2,_]),
     \+ (X is 3 rdiv 4, float(X)),
     \+ \+ (X is 3 rdiv 4, rational(X)),
+    number(3),
+    number(3.0),
+    \+ \+ (X is 1 rdiv 2, number(X)),
+    \+ number(a),
+    \+ number("s"),
+    \+ number(f(x)),
+    \+ number(_),
     \+ rational(3),
     \+ rational(f(_)),
     \+ rational("sdfa"),
@@ -53,6 +81,15 @@ test_queries_on_builtins :-
     \+ rational(structure(f)),
     \+ rational([1,2,3]),
     \+ rational([1,2,_]),
+    callable(foo),
+    callable(foo(x)),
+    callable([a]),
+    \+ callable(1),
+    \+ callable(_),
+    is_list([a,b]),
+    is_list([]),
+    \+ is_list([a|_]),
+    \+ (X = [a|X], is_list(X)),
     compound(functor(compound)),
     compound(f(_)),
     compound([1,2,3]),
@@ -128,3 +165,408 @@ test_queries_on_builtins :-
     \+ \+ findall(X, keysort([X-1,1-1],[2-1,1-1]), [2]).
 
 :- initialization(test_queries_on_builtins).
+
+test_between :-
+    findall(X, between(1, 4, X), [1, 2, 3, 4]),
+    findall(X, between(3, 3, X), [3]),
+    \+ between(4, 3, _),
+    between(1, 4, 2),
+    \+ between(1, 4, 5),
+    findall(X, (between(5, inf, X), ( X >= 7 -> ! ; true )), [5, 6, 7]),
+    findall(X, (between(-1, infinite, X), ( X >= 0 -> ! ; true )), [-1, 0]),
+    between(0, inf, 100),
+    \+ between(0, inf, -1),
+    catch((between(_, 4, _), false), error(instantiation_error, _), true),
+    catch((between(1, _, _), false), error(instantiation_error, _), true),
+    catch((between(a, 4, _), false), error(type_error(integer, a), _), true),
+    catch((between(1, 4.0, _), false), error(type_error(integer, 4.0), _), true),
+    catch((between(1, 4, x), false), error(type_error(integer, x), _), true).
+
+:- initialization(test_between).
+
+key_by_mod_7(I, K-I) :-
+    K is I mod 7.
+
+stable_by_key([]).
+stable_by_key([Pair|Pairs]) :-
+    stable_by_key_(Pairs, Pair).
+
+stable_by_key_([], _).
+stable_by_key_([K2-V2|Pairs], K1-V1) :-
+    (  K1 == K2 -> V1 < V2
+    ;  K1 < K2
+    ),
+    stable_by_key_(Pairs, K2-V2).
+
+test_keysort_stability :-
+    numlist(1, 10000, Is),
+    maplist(key_by_mod_7, Is, Pairs),
+    keysort(Pairs, Sorted),
+    length(Sorted, 10000),
+    stable_by_key(Sorted).
+
+:- initialization(test_keysort_stability).
+
+test_sort_large_input :-
+    numlist(1, 100000, Is),
+    reverse(Is, Rs),
+    append(Rs, Is, Ns),
+    sort(Ns, Sorted),
+    Sorted == Is,
+    sort([c,a,b,a,f(X),1,X,c], [X,1,a,b,c,f(X)]).
+
+:- initialization(test_sort_large_input).
+
+test_msort :-
+    msort([1,1],[1,1]),
+    \+ \+ findall(Sorted, msort([b,a,f(_),1,a,b],Sorted), [[1,a,a,b,b,f(_)]]),
+    msort([X,1], [Y,1]), Y == X,
+    msort([], []),
+    \+ msort([b,a], [b,a]),
+    catch((msort([a|_], _), false), error(instantiation_error, _), true),
+    catch((msort(foo, _), false), error(type_error(list, foo), _), true),
+    catch((msort([a,b|c], _), false), error(type_error(list, [a,b|c]), _), true),
+    catch((msort([a], [b|c]), false), error(type_error(list, [b|c]), _), true).
+
+:- initialization(test_msort).
+
+test_queries_on_cyclic_terms :-
+    X = f(X),
+    cyclic_term(X),
+    \+ acyclic_term(X),
+    acyclic_term(f(a,b)),
+    \+ cyclic_term(f(a,b)),
+    acyclic_term(_),
+    Y = [a,b|Y],
+    cyclic_term(g(Y)),
+    numlist(1, 100000, Ns),
+    acyclic_term(Ns).
+
+:- initialization(test_queries_on_cyclic_terms).
+
+test_equality_of_cyclic_terms :-
+    A = f(A, x),
+    B = f(B, x),
+    A == B,
+    \+ A \== B,
+    C = f(f(C, x), x),
+    A == C,
+    D = f(D, y),
+    A \== D,
+    \+ A == D,
+    L1 = [a,b|L1],
+    L2 = [a,b,a,b|L2],
+    L1 == L2,
+    L3 = [a,c|L3],
+    L1 \== L3.
+
+:- initialization(test_equality_of_cyclic_terms).
+
+test_queries_on_setarg :-
+    T = f(a,b),
+    setarg(1, T, x),
+    T == f(x,b),
+    U = g(a),
+    (  setarg(1, U, y), U == g(y), false
+    ;  U == g(a)
+    ),
+    V = h(a),
+    (  nb_setarg(1, V, z), false
+    ;  V == h(z)
+    ),
+    W = h(a),
+    (  X = b, nb_setarg(1, W, g(X, _)), false
+    ;  W = h(g(Y, _)), Y == b
+    ),
+    \+ setarg(3, f(a,b), c),
+    L = [a,b],
+    setarg(1, L, c),
+    L == [c,b],
+    catch((setarg(_, f(a), b), false), error(instantiation_error, _), true),
+    catch((setarg(1, foo, b), false), error(type_error(compound, foo), _), true).
+
+:- initialization(test_queries_on_setarg).
+
+test_queries_on_repeat :-
+    findall(X, (repeat, member(X, [a,b,c]), !), [a]),
+    findall(X, ((repeat, member(X, [a,b,c]), ! ; true)), [a]),
+    bb_put(repeat_counter, 0),
+    findall(N, (repeat, bb_get(repeat_counter, N0), N is N0 + 1,
+                bb_put(repeat_counter, N), N >= 3, !),
+            [3]).
+
+:- initialization(test_queries_on_repeat).
+
+test_queries_on_forall :-
+    forall(member(X, [1,2,3]), integer(X)),
+    findall(t, forall(member(X, [1,2,3]), integer(X)), [t]),
+    call_cleanup(forall(member(X, [1,2,3]), integer(X)), Det = true),
+    Det == true,
+    \+ forall(member(X, [1,a,3]), integer(X)),
+    catch((forall(member(_, [1]), throw(e)), false), e, true),
+    catch((forall(throw(e), true), false), e, true),
+    bb_put(forall_counter, 0),
+    \+ forall(member(X, [1,a,b,c]),
+              (  bb_get(forall_counter, N0), N is N0 + 1,
+                 bb_put(forall_counter, N), integer(X) )),
+    bb_get(forall_counter, 2).
+
+:- initialization(test_queries_on_forall).
+
+test_queries_on_recorded_database :-
+    recordz(k, first, R1),
+    recordz(k, second, _),
+    recorda(k, zeroth, _),
+    recordz(f(x), other, _),
+    findall(T, recorded(k, T, _), [zeroth, first, second]),
+    findall(T, recorded(f(_), T, _), [other]),
+    recorded(k, first, R),
+    R == R1,
+    erase(R1),
+    findall(T, recorded(k, T, _), [zeroth, second]),
+    catch((erase(R1), false), error(existence_error(db_reference, R1), _), true),
+    catch((recorda(_, t, _), false), error(instantiation_error, _), true).
+
+:- initialization(test_queries_on_recorded_database).
+
+memo_square(X, Y) :-
+    bb_get(memo_counter, N0),
+    N is N0 + 1,
+    bb_put(memo_counter, N),
+    Y is X * X.
+
+test_queries_on_memo :-
+    bb_put(memo_counter, 0),
+    memo(square(3), memo_square(3, Y1)),
+    memo(square(3), memo_square(3, Y2)),
+    memo(square(4), memo_square(4, Y3)),
+    memo(square(3), memo_square(3, Y4)),
+    Y1 == 9, Y2 == 9, Y3 == 16, Y4 == 9,
+    bb_get(memo_counter, 2),
+    findall(X, memo(digits, member(X, [1,2,3])), [1,2,3]),
+    findall(X, memo(digits, member(X, [1,2,3])), [1,2,3]),
+    catch((memo(_, true), false), error(instantiation_error, _), true).
+
+:- initialization(test_queries_on_memo).
+
+:- dynamic(indexed_fact/2).
+
+test_lookup_of_asserted_facts :-
+    forall(between(1, 10000, N),
+           (  V is N * N,
+              assertz(indexed_fact(N, V))
+           )),
+    indexed_fact(5000, 25000000),
+    findall(V, indexed_fact(9999, V), [99980001]),
+    \+ indexed_fact(10001, _),
+    forall(between(1, 10000, N), indexed_fact(N, _)),
+    retractall(indexed_fact(_, _)).
+
+:- initialization(test_lookup_of_asserted_facts).
+
+test_arithmetic_functions :-
+    X1 is min(1, 2.0), X1 == 1,
+    X2 is max(1, 2.0), X2 == 2.0,
+    X3 is min(2.5, 3), X3 == 2.5,
+    X4 is max(2 ^ 70, 3), X4 =:= 2 ^ 70, integer(X4),
+    X5 is max(1 rdiv 2, 1 rdiv 3), X5 =:= 1 rdiv 2, rational(X5), \+ integer(X5),
+    X6 is min(1 rdiv 2, 1), X6 =:= 1 rdiv 2, \+ float(X6),
+    X7 is abs(-3), X7 == 3,
+    X8 is abs(-2.5), X8 == 2.5,
+    X9 is abs(-1 rdiv 2), X9 =:= 1 rdiv 2, \+ float(X9),
+    X10 is sign(-3), X10 == -1,
+    X11 is sign(-2.5), X11 == -1.0,
+    X12 is sign(0.0), X12 == 0.0,
+    X13 is sign(-1 rdiv 2), X13 == -1,
+    X14 is sign(1 rdiv 3), X14 == 1,
+    X15 is msb(1000), X15 == 9,
+    X16 is lsb(1000), X16 == 3,
+    X17 is msb(2 ^ 100), X17 == 100,
+    X18 is lsb(3 * 2 ^ 100), X18 == 100,
+    catch((_ is msb(0), false), error(domain_error(not_less_than_one, 0), _), true),
+    catch((_ is lsb(-4), false), error(domain_error(not_less_than_one, -4), _), true),
+    catch((_ is msb(1.0), false), error(type_error(integer, 1.0), _), true),
+    E = msb(8),
+    X19 is E, X19 == 3.
+
+:- initialization(test_arithmetic_functions).
+
+test_float_functions :-
+    X1 is sqrt(2), float(X1), abs(X1 - 1.4142135623730951) < 1.0e-12,
+    X2 is atan2(1, 1), abs(X2 - pi / 4) < 1.0e-12,
+    X3 is atan(1, 1), X3 == X2,
+    X4 is log(2, 8), abs(X4 - 3.0) < 1.0e-12,
+    X5 is log(e), X5 == 1.0,
+    X6 is exp(0), X6 == 1.0,
+    X7 is sin(pi / 2), abs(X7 - 1.0) < 1.0e-12,
+    X8 is cos(0), X8 == 1.0,
+    X9 is tan(0), X9 == 0.0,
+    X10 is asin(1), abs(X10 - pi / 2) < 1.0e-12,
+    X11 is acos(1), X11 == 0.0,
+    X12 is atan(1), abs(X12 - pi / 4) < 1.0e-12,
+    E = sqrt(-1),
+    catch((_ is E, false), error(evaluation_error(undefined), _), true),
+    catch((_ is sqrt(-2.0), false), error(evaluation_error(undefined), _), true),
+    catch((_ is log(0), false), error(evaluation_error(undefined), _), true),
+    catch((_ is log(1, 2), false), error(evaluation_error(undefined), _), true).
+
+:- initialization(test_float_functions).
+
+test_bitwise_functions :-
+    X1 is \5, X1 == -6,
+    X2 is \(-1), X2 == 0,
+    X3 is \(2 ^ 100), X3 =:= -(2 ^ 100) - 1,
+    X4 is 1 << 100, X4 =:= 2 ^ 100,
+    X5 is (2 ^ 100) >> 98, X5 == 4,
+    X6 is -1 >> 200, X6 == -1,
+    X7 is 3 >> 200, X7 == 0,
+    X8 is 8 << -2, X8 == 2,
+    X9 is 8 >> -2, X9 == 32,
+    X10 is (2 ^ 100) << -100, X10 == 1,
+    X11 is 1 >> -(2 ^ 6), X11 =:= 2 ^ 64,
+    X12 is (2 ^ 70) /\ (2 ^ 70 + 1), X12 =:= 2 ^ 70,
+    X13 is (2 ^ 70) \/ 1, X13 =:= 2 ^ 70 + 1,
+    X14 is (2 ^ 70) xor (2 ^ 70), X14 == 0,
+    catch((_ is \(1.0), false), error(type_error(integer, 1.0), _), true),
+    catch((_ is 1 << 2.0, false), error(type_error(integer, 2.0), _), true).
+
+:- initialization(test_bitwise_functions).
+
+test_integer_power :-
+    X1 is 3 ** 3, X1 == 27,
+    X2 is 3 ** -0, X2 == 1,
+    X3 is 2 ** 100, X3 == 1267650600228229401496703205376,
+    X4 is 2 ** -3, X4 =:= 1 rdiv 8, rational(X4), \+ integer(X4),
+    X5 is (-2) ** -3, X5 =:= -1 rdiv 8,
+    X6 is 1 ** -5, X6 == 1,
+    X7 is (-1) ** -3, X7 == -1,
+    X8 is 2.0 ** 3, X8 == 8.0,
+    X9 is 4 ** 0.5, X9 == 2.0,
+    catch((_ is 0 ** -1, false), error(evaluation_error(undefined), _), true).
+
+:- initialization(test_integer_power).
+
+factorial(0, 1) :- !.
+factorial(N, F) :-
+    N1 is N - 1,
+    factorial(N1, F1),
+    F is N * F1.
+
+test_unbounded_integers :-
+    current_prolog_flag(bounded, false),
+    \+ current_prolog_flag(max_integer, _),
+    \+ current_prolog_flag(min_integer, _),
+    X1 is 2 ** 1000,
+    X1 =:= 2 ^ 1000,
+    X1 >> 1000 =:= 1,
+    X1 - 1 > 0,
+    factorial(30, F),
+    F == 265252859812191058636308480000000,
+    factorial(29, F0),
+    F // F0 =:= 30.
+
+:- initialization(test_unbounded_integers).
+
+test_multibyte_atoms :-
+    atom_length('héllo', 5),
+    atom_length('日本語', 3),
+    sub_atom('héllo', 1, 1, 3, 'é'),
+    sub_atom('héllo', B, 2, A, 'él'), B == 1, A == 2,
+    atom_concat('hé', X, 'héllo'), X == llo,
+    atom_concat(Y, 'llo', 'héllo'), Y == 'hé',
+    atom_chars('日本語', ['日', '本', '語']).
+
+:- initialization(test_multibyte_atoms).
+
+test_string_chars_and_codes :-
+    string_chars("abc", Cs0), Cs0 == [a,b,c],
+    string_chars(S0, [a,b,c]), S0 == "abc",
+    partial_string(S0),
+    string_chars([], []),
+    string_codes("abc", Codes0), Codes0 == [97,98,99],
+    string_codes(S1, [97,98,99]), S1 == "abc",
+    partial_string("ab", S2, T), string_chars(S2, [a,b|T]),
+    catch((string_chars(_, _), false), error(instantiation_error, _), true),
+    catch((string_codes(_, _), false), error(instantiation_error, _), true),
+    string_chars(abc, Cs1), Cs1 == [a,b,c],
+    string_codes(abc, Codes1), Codes1 == [97,98,99],
+    string_chars([97,98], Cs2), Cs2 == [a,b],
+    string_codes([97,98], Codes2), Codes2 == [97,98],
+    catch((string_chars(1, _), false), error(type_error(string, 1), _), true),
+    catch((string_chars([a,bc], _), false), error(type_error(string, [a,bc]), _), true),
+    catch((string_codes(f(x), _), false), error(type_error(string, f(x)), _), true).
+
+:- initialization(test_string_chars_and_codes).
+
+test_term_to_atom :-
+    term_to_atom(1+2, A),
+    A == '1+2',
+    term_to_atom(T, A),
+    T == 1+2,
+    term_to_atom(f('A b', [x], - (1), (a:-b)), B),
+    term_to_atom(T1, B),
+    T1 == f('A b', [x], - (1), (a:-b)),
+    term_to_atom(f(X, Y, X), C),
+    term_to_atom(f(P, Q, R), C),
+    P == R,
+    P \== Q,
+    term_to_atom(1+2, '1 + 2'),
+    \+ term_to_atom(1+2, '2+1'),
+    catch((term_to_atom(_, _), false), error(instantiation_error, _), true),
+    catch((term_to_atom(_, f(x)), false), error(type_error(atom, f(x)), _), true).
+
+:- initialization(test_term_to_atom).
+
+test_char_type :-
+    char_type(a, alpha),
+    char_type('1', alnum),
+    \+ char_type(-, alnum),
+    char_type('7', digit(W)),
+    W == 7,
+    \+ char_type(a, digit(_)),
+    char_type('A', to_lower(L)),
+    L == a,
+    char_type(a, to_upper(U)),
+    U == 'A',
+    char_type(a, to_lower(a)),
+    char_type('1', to_upper('1')),
+    char_type(' ', white),
+    char_type('\t', white),
+    \+ char_type('\n', white),
+    char_type('\n', space),
+    \+ char_type(a, space),
+    char_type(!, ascii_punctuation),
+    char_type('é', alpha),
+    char_type('É', to_lower(E)),
+    E == 'é',
+    char_type('ß', lower),
+    char_type('Ω', upper),
+    char_type('Ω', to_lower(O)),
+    O == 'ω',
+    catch((char_type(_, alpha), false), error(instantiation_error, _), true),
+    catch((char_type(ab, alpha), false), error(type_error(character, ab), _), true),
+    catch((char_type(1, alpha), false), error(type_error(character, 1), _), true).
+
+:- initialization(test_char_type).
+
+test_sub_atom :-
+    sub_atom(abcde, 1, 3, A, S),
+    A == 1, S == bcd,
+    sub_atom(abcde, B, 2, 0, S1),
+    B == 3, S1 == de,
+    sub_atom(abcde, B2, L2, 1, bc), B2 == 1, L2 == 2,
+    findall(B3-S3, sub_atom(abcab, B3, 2, _, S3), [0-ab,1-bc,2-ca,3-ab]),
+    findall(B4-A4, sub_atom(abcab, B4, _, A4, ab), [0-3,3-0]),
+    findall(S5, sub_atom(abc, _, _, _, S5), Subs),
+    Subs == ['',a,ab,abc,'',b,bc,'',c,''],
+    findall(S6, sub_atom(abc, 1, _, _, S6), ['',b,bc]),
+    \+ sub_atom(abc, _, _, _, d),
+    \+ sub_atom(abc, 2, 2, _, _),
+    \+ sub_atom(abc, _, 4, _, _),
+    sub_atom(abc, 0, 0, 3, ''),
+    catch((sub_atom(_, _, _, _, _), false), error(instantiation_error, _), true),
+    catch((sub_atom(f(x), _, _, _, _), false), error(type_error(atom, f(x)), _), true),
+    catch((sub_atom(abc, a, _, _, _), false), error(type_error(integer, a), _), true),
+    catch((sub_atom(abc, -1, _, _, _), false), error(domain_error(not_less_than_zero, -1), _), true).
+
+:- initialization(test_sub_atom).
@@ -0,0 +1,19 @@
+:- module(tests_on_dicts, []).
+
+:- use_module(library(dicts)).
+
+test_queries_on_dicts :-
+    dict_pairs(D0, point, [y-2,x-1]),
+    D0 == dict(point, [x-1,y-2]),
+    get_dict(x, D0, 1),
+    get_dict(y, D0, 2),
+    \+ get_dict(z, D0, _),
+    findall(K-V, get_dict(K, D0, V), [x-1,y-2]),
+    put_dict(x, D0, 3, D1),
+    get_dict(x, D1, 3),
+    get_dict(x, D0, 1),
+    put_dict(w, D1, 0, D2),
+    dict_pairs(D2, point, [w-0,x-3,y-2]),
+    catch((dict_pairs(_, t, [a-1,a-2]), false), error(domain_error(unique_key_pairs, _), _), true).
+
+:- initialization(test_queries_on_dicts).
@@ -0,0 +1,63 @@
+:- module(tests_on_error, []).
+
+:- use_module(library(error)).
+
+test_must_be_accepts_valid_terms :-
+    must_be(integer, 3),
+    must_be(atom, a),
+    must_be(callable, f(x)),
+    must_be(callable, a),
+    must_be(list, [a,b]),
+    must_be(boolean, true),
+    must_be(positive_integer, 1),
+    must_be(nonneg, 0).
+
+:- initialization(test_must_be_accepts_valid_terms).
+
+test_must_be_throws_canonical_errors :-
+    catch((must_be(integer, foo), false), error(type_error(integer, foo), _), true),
+    catch((must_be(integer, _), false), error(instantiation_error, _), true),
+    catch((must_be(positive_integer, -1), false),
+          error(domain_error(positive_integer, -1), _), true),
+    catch((must_be(positive_integer, a), false), error(type_error(integer, a), _), true),
+    catch((must_be(nonneg, -1), false),
+          error(domain_error(not_less_than_zero, -1), _), true),
+    catch((must_be(callable, 3), false), error(type_error(callable, 3), _), true),
+    catch((must_be(atom, f(_)), false), error(type_error(atom, f(_)), _), true),
+    catch((must_be(list, [a|_]), false), error(instantiation_error, _), true),
+    catch((must_be(boolean, yes), false), error(type_error(boolean, yes), _), true).
+
+
+:- initialization(test_must_be_throws_canonical_errors).
+
+test_is_of_type :-
+    is_of_type(var, X),
+    var(X),
+    \+ is_of_type(var, a),
+    is_of_type(integer, 3),
+    \+ is_of_type(integer, 3.0),
+    \+ is_of_type(integer, _),
+    is_of_type(atom, a),
+    \+ is_of_type(atom, "ab"),
+    \+ is_of_type(atom, _),
+    is_of_type(character, a),
+    \+ is_of_type(character, ab),
+    is_of_type(callable, f(_)),
+    \+ is_of_type(callable, 1),
+    \+ is_of_type(callable, _),
+    is_of_type(list, [a,b]),
+    is_of_type(list, []),
+    \+ is_of_type(list, [a|_]),
+    \+ is_of_type(list, _),
+    is_of_type(boolean, false),
+    \+ is_of_type(boolean, no),
+    \+ is_of_type(boolean, _),
+    is_of_type(positive_integer, 1),
+    \+ is_of_type(positive_integer, 0),
+    \+ is_of_type(positive_integer, a),
+    is_of_type(nonneg, 0),
+    \+ is_of_type(nonneg, -1),
+    \+ is_of_type(nonneg, _),
+    var(X).
+
+:- initialization(test_is_of_type).
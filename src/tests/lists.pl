@@ -0,0 +1,94 @@
+:- module(tests_on_lists, []).
+
+:- use_module(library(iso_ext)).
+:- use_module(library(lists)).
+
+test_queries_on_set_operations :-
+    subtract([a,b,c], [b], [a,c]),
+    subtract([a,b,c], [], [a,b,c]),
+    \+ \+ subtract([X,Y], [X], [Y]),
+    \+ \+ ( subtract([X], [_], R), R == [X] ),
+    intersection([a,b], [b,c], [b]),
+    intersection([c,b,a], [a,b], [b,a]),
+    intersection([a], [], []),
+    union([a,b], [b,c], [a,b,c]),
+    union([], [b,c], [b,c]),
+    \+ \+ ( union([X], [Y], U), U == [X,Y] ).
+
+:- initialization(test_queries_on_set_operations).
+
+test_queries_on_membership :-
+    findall(X, member(X, [a,b,c]), [a,b,c]),
+    findall(t, memberchk(b, [a,b,c]), [t]),
+    findall(X, memberchk(X, [a,b,c]), [a]),
+    \+ memberchk(z, [a,b]),
+    \+ member(_, []).
+
+:- initialization(test_queries_on_membership).
+
+test_queries_on_append :-
+    findall(X-Y, append(X, Y, [a,b]), [[]-[a,b], [a]-[b], [a,b]-[]]),
+    partial_string([d,e,f], PS, []),
+    append([a,b,c], PS, L),
+    L == [a,b,c,d,e,f],
+    partial_string([a,b], Open, T),
+    append([c], T1, T),
+    Open = [a,b,c|T1],
+    T1 = [],
+    Open == [a,b,c],
+    append([[a],[],[b,c],[d]], [a,b,c,d]),
+    append([], []).
+
+:- initialization(test_queries_on_append).
+
+test_queries_on_flatten :-
+    flatten([a,[b,[c,[]],d]], [a,b,c,d]),
+    flatten([], []),
+    flatten(a, [a]),
+    \+ \+ ( flatten([X,[Y]], F), F == [X,Y] ),
+    L = [a|L],
+    \+ flatten(L, _).
+
+:- initialization(test_queries_on_flatten).
+
+test_queries_on_proper_length :-
+    proper_length([a,b,c], 3),
+    proper_length([], 0),
+    \+ proper_length([a|_], _),
+    \+ proper_length([a|b], _),
+    \+ ( X = [a|X], proper_length(X, _) ).
+
+:- initialization(test_queries_on_proper_length).
+
+compare_by_key(Order, K1-_, K2-_) :-
+    compare(Order, K1, K2).
+
+test_queries_on_predsort :-
+    predsort(compare, [c,a,b,a], [a,b,c]),
+    predsort(compare_by_key, [2-x,1-y,2-z,3-w], [1-y,2-x,3-w]),
+    predsort(compare, [], []),
+    \+ \+ ( predsort(compare, [X,a], S), S == [X,a] ),
+    catch((predsort(compare, [a|_], _), false), error(instantiation_error, _), true),
+    catch((predsort(compare, foo, _), false), error(type_error(list, foo), _), true),
+    catch((predsort(1, [a,b], _), false), error(type_error(callable, _), _), true).
+
+:- initialization(test_queries_on_predsort).
+
+test_queries_on_nth :-
+    nth0(0, [a,b,c], a),
+    nth0(2, [a,b,c], c),
+    \+ nth0(3, [a,b,c], _),
+    \+ nth0(-1, [a,b,c], _),
+    nth1(1, [a,b,c], a),
+    nth1(3, [a,b,c], c),
+    \+ nth1(0, [a,b,c], _),
+    \+ nth1(4, [a,b,c], _),
+    findall(N-E, nth0(N, [a,b,c], E), [0-a,1-b,2-c]),
+    findall(N-E, nth1(N, [a,b,c], E), [1-a,2-b,3-c]),
+    findall(N, nth1(N, [a,b,a], a), [1,3]),
+    findall(N, (nth0(N, L, x), ( N >= 2 -> ! ; true )), [0,1,2]),
+    \+ \+ ( nth1(2, L1, x), L1 = [_,x|_] ),
+    catch((nth0(a, [a,b], _), false), error(type_error(integer, a), _), true),
+    catch((nth1(1.0, [a,b], _), false), error(type_error(integer, 1.0), _), true).
+
+:- initialization(test_queries_on_nth).
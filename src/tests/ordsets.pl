@@ -0,0 +1,18 @@
+:- module(tests_on_ordsets, []).
+
+:- use_module(library(ordsets)).
+
+test_queries_on_ordsets :-
+    list_to_ord_set([c,a,b,a], [a,b,c]),
+    ord_union([1,3], [2,3], [1,2,3]),
+    ord_union([], [a], [a]),
+    ord_intersection([1,2,3], [2,3,4], [2,3]),
+    ord_subtract([1,2,3], [2], [1,3]),
+    ord_memberchk(b, [a,b,c]),
+    \+ ord_memberchk(d, [a,b,c]),
+    is_ordset([1,2,3]),
+    \+ is_ordset([2,1]),
+    \+ is_ordset([1,1]),
+    \+ is_ordset([a|_]).
+
+:- initialization(test_queries_on_ordsets).
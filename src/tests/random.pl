@@ -0,0 +1,46 @@
+:- module(tests_on_random, []).
+
+:- use_module(library(between)).
+:- use_module(library(iso_ext)).
+:- use_module(library(lists)).
+:- use_module(library(random)).
+
+random_sequence(Seed, Xs) :-
+    set_random(seed(Seed)),
+    findall(X, (between(1, 20, _), random_between(1, 6, X)), Xs).
+
+test_queries_on_random :-
+    set_random(seed(42)),
+    random(F),
+    0.0 =< F, F < 1.0,
+    random_sequence(7, Xs),
+    random_sequence(7, Ys),
+    Xs == Ys,
+    forall(member(X, Xs), (1 =< X, X =< 6)),
+    random_between(3, 3, 3),
+    \+ random_between(3, 2, _),
+    catch((random_between(a, 2, _), false), error(type_error(integer, a), _), true),
+    random_member(M, [a,b,c]),
+    memberchk(M, [a,b,c]),
+    \+ random_member(_, []),
+    set_random(seed(9)),
+    findall(M0, (between(1, 5, _), random_member(M0, [a,b,c])), Ms0),
+    set_random(seed(9)),
+    findall(M1, (between(1, 5, _), random_member(M1, [a,b,c])), Ms1),
+    Ms0 == Ms1.
+
+:- initialization(test_queries_on_random).
+
+test_queries_on_random_permutation :-
+    set_random(seed(3)),
+    random_permutation([1,2,3,4,5], P0),
+    sort(P0, [1,2,3,4,5]),
+    set_random(seed(3)),
+    random_permutation([1,2,3,4,5], P1),
+    P0 == P1,
+    random_permutation([], []),
+    random_permutation([b,a,b], P2),
+    msort(P2, [a,b,b]),
+    catch((random_permutation(foo, _), false), error(type_error(list, foo), _), true).
+
+:- initialization(test_queries_on_random_permutation).
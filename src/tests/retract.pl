@@ -0,0 +1,42 @@
+:- module(tests_on_retract, []).
+
+:- dynamic(r/1).
+
+test_retract_on_backtracking :-
+    assertz(r(1)), assertz(r(2)), assertz(r(3)),
+    findall(X-Y, (r(X), retract(r(Y))), [1-1,1-2,1-3]),
+    \+ r(_),
+    assertz(r(1)), assertz(r(2)), assertz(r(3)),
+    findall(Y, (retract(r(Y)), (Y == 1 -> retract(r(3)) ; true)), [1,2]),
+    \+ r(_),
+    assertz(r(1)), assertz(r(2)),
+    findall(Y, (retract(r(Y)), (Y == 1 -> assertz(r(4)) ; true)), [1,2]),
+    findall(X, r(X), [4]),
+    retract(r(4)).
+
+:- initialization(test_retract_on_backtracking).
+
+:- dynamic(s/1).
+
+test_assertz_during_iteration :-
+    assertz(s(1)), assertz(s(2)),
+    findall(X, (s(X), Y is X + 10, assertz(s(Y))), [1,2]),
+    findall(X, s(X), [1,2,11,12]),
+    findall(X, (s(X), X < 10, Y is -X, asserta(s(Y))), [1,2]),
+    findall(X, s(X), [-2,-1,1,2,11,12]),
+    retractall(s(_)).
+
+:- initialization(test_assertz_during_iteration).
+
+:- dynamic(t/1).
+
+test_retract_of_general_clause :-
+    assertz(t(_)),
+    retract(t(1)),
+    \+ t(_),
+    assertz(t(a)), assertz(t(b)),
+    findall(X, (retract(t(X)), (X == a -> retract(t(b)), assertz(t(b)) ; true)), [a]),
+    findall(X, t(X), [b]),
+    retract(t(b)).
+
+:- initialization(test_retract_of_general_clause).
@@ -162,7 +162,10 @@ instruction_match(Term, VarList) :-
 
 submit_query_and_print_results_(Term, VarList) :-
     '$get_b_value'(B),
-    '$call'(Term),
+    (  bb_get('$tracing', true) ->
+       debug:'$trace_goal'(Term)
+    ;  '$call'(Term)
+    ),
     write_eqs_and_read_input(B, VarList),
     !.
 submit_query_and_print_results_(_, _) :-
@@ -451,6 +451,9 @@ impl fmt::Display for SessionError {
             &SessionError::QueryCannotBeDefinedAsFact => {
                 write!(f, "queries cannot be defined as facts.")
             }
+            &SessionError::Timeout => {
+                write!(f, "the query did not complete in time.")
+            }
             &SessionError::ModuleCannotImportSelf(ref module_name) => {
                 write!(f, "modules ({}, in this case) cannot import themselves.",
                        module_name)
@@ -629,6 +632,9 @@ impl fmt::Display for ArithmeticInstruction {
             &ArithmeticInstruction::ATan2(ref a1, ref a2, ref t) => {
                 write!(f, "atan2 {}, {}, @{}", a1, a2, t)
             }
+            &ArithmeticInstruction::LogBase(ref a1, ref a2, ref t) => {
+                write!(f, "log {}, {}, @{}", a1, a2, t)
+            }
             &ArithmeticInstruction::Plus(ref a, ref t) => write!(f, "plus {}, @{}", a, t),
             &ArithmeticInstruction::Sign(ref a, ref t) => write!(f, "sign {}, @{}", a, t),
             &ArithmeticInstruction::Neg(ref a, ref t) => write!(f, "neg {}, @{}", a, t),
@@ -644,6 +650,8 @@ impl fmt::Display for ArithmeticInstruction {
             &ArithmeticInstruction::BitwiseComplement(ref a, ref t) => {
                 write!(f, "bitwise_complement {}, @{}", a, t)
             }
+            &ArithmeticInstruction::Msb(ref a, ref t) => write!(f, "msb {}, @{}", a, t),
+            &ArithmeticInstruction::Lsb(ref a, ref t) => write!(f, "lsb {}, @{}", a, t),
             &ArithmeticInstruction::Truncate(ref a, ref t) => write!(f, "truncate {}, @{}", a, t),
             &ArithmeticInstruction::Round(ref a, ref t) => write!(f, "round {}, @{}", a, t),
             &ArithmeticInstruction::Ceiling(ref a, ref t) => write!(f, "ceiling {}, @{}", a, t),
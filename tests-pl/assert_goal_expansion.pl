@@ -0,0 +1,13 @@
+goal_expansion(my_macro(X), real_goal(X)).
+
+real_goal(X) :- write(real_goal(X)), nl.
+
+test :-
+    Body =.. [my_macro, X],
+    assertz((p(X) :- Body)),
+    p(1),
+    clause(p(Y), ExpandedBody),
+    (  ExpandedBody == real_goal(Y) -> write(expanded) ; write(unexpanded) ),
+    nl.
+
+:- initialization(test).
@@ -0,0 +1,3 @@
+﻿fact(a).
+
+:- initialization((fact(X), write(X), nl)).
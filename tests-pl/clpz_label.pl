@@ -0,0 +1,9 @@
+:- use_module(library(clpz)).
+
+test :-
+    findall(X-Y, (X in 1..3, Y in 1..3, X + Y #= 4, label([X,Y])), Solutions),
+    write(Solutions), nl,
+    (  X in 1..3, X #> 3 -> write(consistent) ; write(inconsistent) ),
+    nl.
+
+:- initialization(test).
@@ -0,0 +1,15 @@
+:- use_module(library(dif)).
+:- use_module(library(lists)).
+
+test :-
+    dif(X, Y),
+    copy_term(f(X,Y), f(A,B), Gs),
+    length(Gs, N),
+    write(N), nl,
+    (  Gs = [dif:dif(C, D)], C == A, D == B ->
+       write(single_dif)
+    ;  write(Gs)
+    ),
+    nl.
+
+:- initialization(test).
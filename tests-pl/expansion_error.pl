@@ -0,0 +1,11 @@
+term_expansion(broken, _) :- throw(oops).
+
+test :-
+    catch(expand_term(broken, _),
+          error(expansion_error(user:term_expansion(Term, _), E), Context),
+          true),
+    write(expansion_error(Term, E)), nl,
+    (  Context = load/1:Line, integer(Line) -> write(located) ; write(unlocated) ),
+    nl.
+
+:- initialization(test).
@@ -0,0 +1,10 @@
+:- use_module(library(charsio)).
+
+test :-
+    catch(read_term_from_chars("a('b\nc').", _),
+          error(syntax_error(E), _:Line),
+          true),
+    write(E-Line),
+    nl.
+
+:- initialization(test).
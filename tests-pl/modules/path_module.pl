@@ -0,0 +1,3 @@
+:- module(path_module, [greeting/1]).
+
+greeting(hello).
@@ -0,0 +1,2 @@
+:- op(700, xf, isa).
+:- op(700, xfx, isa).
@@ -0,0 +1,4 @@
+:- op(200, fy, isa).
+:- op(700, xfx, isa).
+
+t(isa a isa b).
@@ -0,0 +1,17 @@
+test :-
+    current_prolog_flag(double_quotes, DQ0),
+    open('tests-pl/read_term_double_quotes.txt', read, Stream),
+    read_term(Stream, Codes, [double_quotes(codes)]),
+    read_term(Stream, Chars, [double_quotes(chars)]),
+    close(Stream),
+    write(Codes), nl,
+    write(Chars), nl,
+    current_prolog_flag(double_quotes, DQ),
+    (  DQ == DQ0 -> write(unchanged) ; write(changed) ),
+    nl,
+    catch(( read_term(user_input, _, [double_quotes(_)]), false ),
+          error(instantiation_error, read_term/3),
+          write(instantiation_error)),
+    nl.
+
+:- initialization(test).
@@ -0,0 +1,17 @@
+:- use_module(library(charsio)).
+
+:- op(700, xfx, ===>).
+
+test :-
+    read_term_from_atom('a ===> f(X, Y)', T, [variable_names(VNs)]),
+    T = (L ===> f(A, B)),
+    writeq(L), nl,
+    (  VNs == ['X'=A, 'Y'=B] ->
+       write(variable_names), nl
+    ;  true
+    ),
+    catch(read_term_from_atom('a b', _, []),
+          error(syntax_error(_), Context),
+          (writeq(Context), nl)).
+
+:- initialization(test).
@@ -0,0 +1,11 @@
+test :-
+    open('tests-pl/read_term_variable_names.txt', read, Stream),
+    read_term(Stream, Term, [variable_names(Bindings)]),
+    read_term(Stream, EOF, [variable_names(EOFBindings)]),
+    close(Stream),
+    Term = foo(A, B, C, D),
+    (  Bindings == ['X'=A, 'Y'=B, '_Z'=C], A == D -> write(bound) ; write(unbound) ),
+    nl,
+    write(EOF-EOFBindings), nl.
+
+:- initialization(test).
@@ -0,0 +1,13 @@
+:- use_module(library(pio)).
+
+test :-
+    open('tests-pl/stream_to_lazy_list.txt', read, Stream, [reposition(true)]),
+    stream_to_lazy_list(Stream, Xs),
+    (  var(Xs) -> write(unread) ; write(read) ),
+    nl,
+    Xs = [A,B|_],
+    write([A,B]),
+    nl,
+    close(Stream).
+
+:- initialization(test).
@@ -0,0 +1,22 @@
+:- use_module(library(tabling)).
+
+:- table path/2.
+
+path(X, Y) :- path(X, Z), edge(Z, Y).
+path(X, Y) :- edge(X, Y).
+
+edge(a, b).
+edge(b, c).
+edge(c, a).
+edge(c, d).
+
+test :-
+    findall(Y, path(a, Y), Ys0),
+    sort(Ys0, Ys),
+    length(Ys0, N0),
+    length(Ys, N),
+    write(Ys), nl,
+    (  N0 =:= N -> write(no_duplicates) ; write(duplicates) ),
+    nl.
+
+:- initialization(test).
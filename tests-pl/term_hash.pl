@@ -0,0 +1,36 @@
+:- use_module(library(terms)).
+
+test :-
+    term_hash(f(a, "bc", 1, 2.5, [x]), H1),
+    term_hash(f(a, "bc", 1, 2.5, [x]), H2),
+    (  integer(H1), H1 == H2 -> write(equal) ; write(unequal) ),
+    nl,
+    term_hash(f(a), H3),
+    term_hash(f(b), H4),
+    term_hash('1', H5),
+    term_hash(1, H6),
+    (  H3 =\= H4, H5 =\= H6 -> write(distinct) ; write(collision) ),
+    nl,
+    term_hash(f(_), H7),
+    (  var(H7) -> write(unbound) ; write(bound) ),
+    nl,
+    term_hash(f(a, g(_)), 1, 100, H8),
+    term_hash(f(a, g(b)), 1, 100, H9),
+    (  H8 == H9, H8 >= 0, H8 < 100 -> write(depth_limited) ; write(depth_ignored) ),
+    nl,
+    C = f(C),
+    catch((term_hash(C, _), write(hashed)),
+          error(type_error(acyclic_term, _), term_hash/2),
+          write(cyclic)),
+    nl,
+    term_hash(C, 3, 100, H10),
+    (  integer(H10) -> write(cyclic_depth_limited) ; write(cyclic_unbound) ),
+    nl,
+    term_hash(4294967295, H11),
+    term_hash(-1, H12),
+    term_hash(18446744073709551616, H13),
+    term_hash(0, H14),
+    (  H11 =\= H12, H13 =\= H14 -> write(integers_distinct) ; write(integers_collide) ),
+    nl.
+
+:- initialization(test).
@@ -0,0 +1,25 @@
+:- use_module(library(debug)).
+:- use_module(library(lists)).
+
+:- dynamic(max/3).
+:- dynamic(first/2).
+:- dynamic(local/1).
+
+max(X, Y, Z) :-
+    (  X >= Y, !, Z = X
+    ;  Z = Y
+    ).
+
+first(Xs, X) :-
+    (  true ->
+       member(X, Xs), !
+    ;  X = none
+    ).
+first(_, last).
+
+local(X) :-
+    (  call((member(X, [a, b]), !))
+    ;  X = c
+    ).
+
+:- leash([]).
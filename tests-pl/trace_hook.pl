@@ -0,0 +1,14 @@
+:- use_module(library(debug)).
+
+:- dynamic(called/1).
+:- dynamic(len/2).
+
+len([], 0).
+len([_|Xs], N) :-
+    len(Xs, N0),
+    N is N0 + 1.
+
+'$trace_hook'(call, len(Xs, _)) :-
+    !,
+    assertz(called(Xs)).
+'$trace_hook'(_, _).
@@ -0,0 +1,17 @@
+:- use_module('modules/path_module').
+:- use_module('modules/path_module.pl').
+
+:- dynamic(library_directory/1).
+
+test :-
+    greeting(X),
+    write(X), nl,
+    catch(use_module('modules/missing'),
+          error(E, _),
+          (write(E), nl)),
+    assertz(library_directory('tests-pl/modules')),
+    use_module(library(path_module)),
+    path_module:greeting(Y),
+    write(Y), nl.
+
+:- initialization(test).
@@ -0,0 +1,18 @@
+:- use_module(library(charsio)).
+:- use_module(library(lists)).
+
+test :-
+    X = f(X),
+    catch(write_term(X, [cycles(false)]),
+          error(type_error(acyclic_term, _), write_term/3),
+          (write(rejected), nl)),
+    (  write_term_to_chars(X, [], Chars),
+       append(_, ['.','.','.'|_], Chars) ->
+       write(elided)
+    ;  write(not_elided)
+    ),
+    nl,
+    write_term(f(a), [cycles(false)]),
+    nl.
+
+:- initialization(test).
@@ -0,0 +1,8 @@
+test :-
+    write_term(a+b*c, [fullstop(true)]), nl,
+    write_term(a+b*c, []), nl,
+    writeq(a*(b+c)), nl,
+    write_term(f(a,g(b,c)), [spacing(next_argument)]), nl,
+    write_term(f(a,b), [spacing(standard)]), nl.
+
+:- initialization(test).
@@ -0,0 +1,13 @@
+test :-
+    writeq('hello world'), nl,
+    writeq('Atom'), nl,
+    writeq([]), nl,
+    writeq({}), nl,
+    writeq('it''s'), nl,
+    writeq(''), nl,
+    writeq(''''''), nl,
+    writeq('\x1b\'), nl,
+    writeq(','), nl,
+    writeq('|'), nl.
+
+:- initialization(test).
@@ -0,0 +1,977 @@
+use scryer_prolog::machine::{
+    Addr, Constant, EnvPolicy, LoadError, LoadWarning, Machine, MachineBuilder, MachineState,
+    OpDecl, PredicateKind, ResourceLimits, SessionError, Stream, Term,
+};
+
+use prolog_parser::ast::{XF, XFX, YFX};
+use prolog_parser::clause_name;
+
+use indexmap::IndexMap;
+
+use std::cell::RefCell;
+use std::fs;
+use std::io::{self, Write};
+use std::rc::Rc;
+use std::time::Duration;
+
+// builds a machine reading from an empty user_input and writing to a
+// string stream, which is returned alongside it.
+fn build_machine(configure: impl FnOnce(MachineBuilder) -> MachineBuilder) -> (Machine, Stream) {
+    let output = Stream::from(String::new());
+
+    let wam = configure(MachineBuilder::new(
+        Stream::from(""),
+        output.clone(),
+        Stream::from(String::new()),
+    ))
+    .build()
+    .unwrap();
+
+    (wam, output)
+}
+
+fn output_string(output: &Stream) -> String {
+    String::from_utf8(output.bytes().unwrap().clone()).unwrap()
+}
+
+// loads `source` into a machine built by `build_machine` and checks what
+// it wrote to user_output.
+fn load_test(
+    configure: impl FnOnce(MachineBuilder) -> MachineBuilder,
+    source: impl Into<Stream>,
+    expected: &str,
+) {
+    let (mut wam, output) = build_machine(configure);
+
+    wam.load_file("test.pl".into(), source.into());
+
+    assert_eq!(output_string(&output), expected);
+}
+
+#[test]
+fn library_paths() {
+    let lib_dir = std::env::temp_dir().join(format!("scryer-library-paths-{}", std::process::id()));
+
+    fs::create_dir_all(&lib_dir).unwrap();
+    fs::write(
+        lib_dir.join("mymod.pl"),
+        ":- module(mymod, [mymod_greeting/1]).\n\nmymod_greeting(hello).\n",
+    )
+    .unwrap();
+
+    load_test(
+        |builder| builder.with_library_paths(vec![lib_dir.clone()]),
+        ":- use_module(library(mymod)).\n\
+         :- initialization((mymod_greeting(X), write(X), nl)).\n",
+        "hello\n",
+    );
+
+    fs::remove_dir_all(&lib_dir).unwrap();
+}
+
+#[test]
+fn embedded_library() {
+    load_test(
+        |builder| {
+            builder.with_embedded_library(
+                "greetings",
+                ":- module(greetings, [greet/1]).\n\ngreet(Name) :- write(hello(Name)), nl.\n",
+            )
+        },
+        ":- use_module(library(greetings)).\n\
+         :- initialization(greet(world)).\n",
+        "hello(world)\n",
+    );
+}
+
+#[test]
+fn reset_dynamic_db() {
+    let (mut wam, output) = build_machine(|builder| builder);
+
+    wam.load_file(
+        "assert_facts.pl".into(),
+        Stream::from(
+            ":- use_module(library(iso_ext)).\n\
+             :- dynamic(fact/1).\n\
+             :- initialization((assertz(fact(1)), assertz(fact(2)), bb_put(key, 1))).\n\
+             :- initialization(bb_put('$key', 1)).\n",
+        ),
+    );
+
+    wam.reset_dynamic_db();
+
+    wam.load_file(
+        "check_facts.pl".into(),
+        Stream::from(
+            ":- use_module(library(iso_ext)).\n\
+             :- use_module(library(lists)).\n\
+             :- initialization(((fact(_) -> write(present) ; write(gone)), nl)).\n\
+             :- initialization(((bb_get(key, _) -> write(has_key) ; write(no_key)), nl)).\n\
+             :- initialization(((bb_get('$key', _) -> write(has_key) ; write(no_key)), nl)).\n\
+             :- initialization((length([a,b], N), write(N), nl)).\n",
+        ),
+    );
+
+    assert_eq!(output_string(&output), "gone\nno_key\nhas_key\n2\n");
+}
+
+#[test]
+fn heap_limit() {
+    load_test(
+        |builder| {
+            builder.with_resource_limits(ResourceLimits {
+                max_heap_cells: Some(2_000_000),
+                ..ResourceLimits::default()
+            })
+        },
+        ":- use_module(library(lists)).\n\
+         grow(L) :- grow([x|L]).\n\
+         :- initialization((catch(grow([]), error(resource_error(R), _), write(R)), nl)).\n\
+         :- initialization((length(L, 3), length(L, N), write(N), nl)).\n",
+        "heap\n3\n",
+    );
+}
+
+#[test]
+fn stack_frame_limit() {
+    load_test(
+        |builder| {
+            builder.with_resource_limits(ResourceLimits {
+                max_stack_frames: Some(10_000),
+                ..ResourceLimits::default()
+            })
+        },
+        "loop :- loop, write(unreachable).\n\
+         :- initialization((catch(loop, error(resource_error(R), C), write(R-C)), nl)).\n",
+        "stack-loop/0\n",
+    );
+}
+
+#[test]
+fn trail_limit() {
+    load_test(
+        |builder| {
+            builder.with_resource_limits(ResourceLimits {
+                max_trail: Some(10_000),
+                ..ResourceLimits::default()
+            })
+        },
+        ":- use_module(library(lists)).\n\
+         bind_all([]).\n\
+         bind_all([x|Xs]) :- bind_all(Xs).\n\
+         :- initialization((catch((length(L, 100000), (true ; true), bind_all(L)),\n\
+                                  error(resource_error(R), _),\n\
+                                  write(R)),\n\
+                            nl)).\n",
+        "trail\n",
+    );
+}
+
+#[test]
+fn trail_tidied_on_cut() {
+    load_test(
+        |builder| {
+            builder.with_resource_limits(ResourceLimits {
+                max_trail: Some(1_000),
+                ..ResourceLimits::default()
+            })
+        },
+        "step(X, Y) :- Y = X, !.\n\
+         step(_, _).\n\
+         loop(0) :- !.\n\
+         loop(N) :- step(N, _), N1 is N - 1, loop(N1).\n\
+         :- initialization((catch(loop(100000), error(resource_error(R), _), (write(R), nl)),\n\
+                            write(done), nl)).\n",
+        "done\n",
+    );
+}
+
+const ROLLS: &str = ":- use_module(library(between)).\n\
+                     :- use_module(library(random)).\n\
+                     :- initialization((findall(X, (between(1, 20, _), random_between(1, 1000, X)), Xs),\n\
+                                        write(Xs))).\n";
+
+fn seeded_rolls(seed: u64) -> String {
+    let (mut wam, output) = build_machine(|builder| builder.with_rng_seed(seed));
+
+    wam.load_file("seeded_rolls.pl".into(), Stream::from(ROLLS));
+
+    output_string(&output)
+}
+
+#[test]
+fn rng_seed() {
+    assert_eq!(seeded_rolls(1), seeded_rolls(1));
+    assert_ne!(seeded_rolls(1), seeded_rolls(2));
+}
+
+#[test]
+fn rng_is_per_machine() {
+    let (mut wam, output) = build_machine(|builder| builder.with_rng_seed(1));
+    let _other = build_machine(|builder| builder.with_rng_seed(2));
+
+    wam.load_file("seeded_rolls.pl".into(), Stream::from(ROLLS));
+
+    assert_eq!(output_string(&output), seeded_rolls(1));
+}
+
+#[test]
+fn injected_clock() {
+    load_test(
+        |builder| builder.with_clock(|| 1_000_000_000.5),
+        ":- use_module(library(time)).\n\
+         :- initialization((get_time(T), write(T), nl)).\n\
+         :- initialization((get_time(T), format_time(atom(A), '%Y', T), write(A), nl)).\n",
+        "1000000000.5\n2001\n",
+    );
+}
+
+#[test]
+fn allowed_env_vars() {
+    let mut vars = IndexMap::new();
+    vars.insert("GREETING".to_string(), "hello".to_string());
+    vars.insert("NAME".to_string(), "scryer".to_string());
+
+    load_test(
+        |builder| builder.with_env_policy(EnvPolicy::Allow(vars)),
+        ":- use_module(library(format)).\n\
+         :- use_module(library(os)).\n\
+         :- initialization((getenv(\"GREETING\", V), format(\"~s~n\", [V]))).\n\
+         :- initialization(((getenv(\"HOME\", _) -> write(home) ; write(no_home)), nl)).\n\
+         :- initialization((setenv(\"NAME\", \"prolog\"), unsetenv(\"GREETING\"),\n\
+                            environ(Vs), write(Vs), nl)).\n",
+        "hello\nno_home\n[[N,A,M,E]=[p,r,o,l,o,g]]\n",
+    );
+}
+
+#[test]
+fn denied_env_vars() {
+    load_test(
+        |builder| builder.with_env_policy(EnvPolicy::Deny),
+        ":- use_module(library(os)).\n\
+         :- initialization(((getenv(\"PATH\", _) -> write(path) ; write(no_path)), nl)).\n\
+         :- initialization((environ(Vs), write(Vs), nl)).\n\
+         :- initialization((catch(setenv(\"PATH\", \"/bin\"), error(E, _), true), write(E), nl)).\n",
+        "no_path\n[]\npermission_error(modify,env_var,[P,A,T,H])\n",
+    );
+}
+
+#[test]
+fn process_execution_disabled() {
+    load_test(
+        |builder| builder.with_process_execution(false),
+        ":- use_module(library(os)).\n\
+         :- initialization((catch(shell(\"true\", _), error(E, _), true), write(E), nl)).\n",
+        "permission_error(execute,shell,[t,r,u,e])\n",
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn process_execution_enabled() {
+    load_test(
+        |builder| builder.with_process_execution(true),
+        ":- use_module(library(os)).\n\
+         :- initialization((shell(\"exit 3\", S), write(S), nl)).\n",
+        "3\n",
+    );
+}
+
+#[test]
+fn read_line_to_string() {
+    let output = Stream::from(String::new());
+
+    let mut wam = MachineBuilder::new(
+        Stream::from("first\r\nsecond\nthird"),
+        output.clone(),
+        Stream::from(String::new()),
+    )
+    .build()
+    .unwrap();
+
+    wam.load_file(
+        "read_line_to_string.pl".into(),
+        Stream::from(
+            ":- use_module(library(charsio)).\n\
+             :- use_module(library(format)).\n\
+             read_lines :-\n\
+                 read_line_to_string(user_input, L),\n\
+                 (   L == end_of_file -> write(L), nl\n\
+                 ;   format(\"~s|~n\", [L]), read_lines\n\
+                 ).\n\
+             :- initialization(read_lines).\n",
+        ),
+    );
+
+    assert_eq!(
+        output_string(&output),
+        "first|\nsecond|\nthird|\nend_of_file\n"
+    );
+}
+
+#[test]
+fn char_conversion() {
+    load_test(
+        |builder| builder,
+        ":- use_module(library(charsio)).\n\
+         convert :-\n\
+             char_conversion(b, c),\n\
+             current_char_conversion(b, C), write(C), nl,\n\
+             set_prolog_flag(char_conversion, on),\n\
+             read_term_from_chars(\"f(b, 'b').\", T0),\n\
+             set_prolog_flag(char_conversion, off),\n\
+             read_term_from_chars(\"f(b, 'b').\", T1),\n\
+             char_conversion(b, b),\n\
+             writeq(T0-T1), nl,\n\
+             \\+ current_char_conversion(b, _).\n\
+         :- initialization(convert).\n",
+        "c\nf(c,b)-f(b,b)\n",
+    );
+}
+
+#[test]
+fn stream_encoding() {
+    let file = std::env::temp_dir().join(format!("scryer-stream-encoding-{}", std::process::id()));
+
+    fs::write(&file, b"a\xe9\n").unwrap();
+
+    load_test(
+        |builder| builder,
+        format!(
+            "test :-\n\
+                 File = '{}',\n\
+                 open(File, read, Octets, [encoding(octet), reposition(true)]),\n\
+                 stream_property(Octets, file_name(File)),\n\
+                 stream_property(Octets, position(Pos)),\n\
+                 get_char(Octets, C0),\n\
+                 peek_char(Octets, C1),\n\
+                 get_char(Octets, C1),\n\
+                 set_stream_position(Octets, Pos),\n\
+                 get_char(Octets, C2),\n\
+                 close(Octets),\n\
+                 write([C0, C1, C2]), nl,\n\
+                 open(File, read, Utf8),\n\
+                 get_char(Utf8, C3),\n\
+                 write(C3), nl,\n\
+                 catch(get_char(Utf8, _), error(io_error(encoding, _), _), write(invalid)),\n\
+                 nl,\n\
+                 close(Utf8),\n\
+                 catch(open(File, read, _, [encoding(latin1)]),\n\
+                       error(domain_error(stream_option, encoding(latin1)), _),\n\
+                       write(rejected)),\n\
+                 nl,\n\
+                 catch(open(File, append, _, [encoding(octet)]),\n\
+                       error(domain_error(stream_option, encoding(octet)), _),\n\
+                       write(input_only)),\n\
+                 nl.\n\
+             :- initialization(test).\n",
+            file.display()
+        ),
+        "[a,\u{e9},a]\na\ninvalid\nrejected\ninput_only\n",
+    );
+
+    fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn byte_io() {
+    let file = std::env::temp_dir().join(format!("scryer-byte-io-{}", std::process::id()));
+
+    load_test(
+        |builder| builder,
+        format!(
+            "test :-\n\
+                 File = '{}',\n\
+                 open(File, write, Out, [type(binary)]),\n\
+                 put_byte(Out, 0),\n\
+                 put_byte(Out, 127),\n\
+                 put_byte(Out, 255),\n\
+                 close(Out),\n\
+                 open(File, read, In, [type(binary)]),\n\
+                 peek_byte(In, P),\n\
+                 catch(get_char(In, _),\n\
+                       error(permission_error(input, binary_stream, _), _),\n\
+                       write(binary_stream)),\n\
+                 nl,\n\
+                 get_byte(In, B0),\n\
+                 get_byte(In, B1),\n\
+                 get_byte(In, B2),\n\
+                 get_byte(In, EOF),\n\
+                 close(In),\n\
+                 write([P, B0, B1, B2, EOF]),\n\
+                 nl.\n\
+             :- initialization(test).\n",
+            file.display()
+        ),
+        "binary_stream\n[0,0,127,255,-1]\n",
+    );
+
+    fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn phrase_from_file() {
+    let file = std::env::temp_dir().join(format!("scryer-phrase-from-file-{}", std::process::id()));
+
+    fs::write(&file, "hello world\n".repeat(500)).unwrap();
+
+    load_test(
+        |builder| builder,
+        format!(
+            ":- use_module(library(dcgs)).\n\
+             :- use_module(library(pio)).\n\
+             rest(Rest, Rest, []).\n\
+             test :-\n\
+                 File = '{}',\n\
+                 (  phrase_from_file((\"hello world\\n\", rest(Rest)), File),\n\
+                    '$skip_max_list'(_, -1, Rest, Tail),\n\
+                    var(Tail) -> write(lazy)\n\
+                 ;  write(eager)\n\
+                 ),\n\
+                 nl,\n\
+                 (  phrase_from_file(\"goodbye\", File) -> write(parsed) ; write(failed) ),\n\
+                 nl,\n\
+                 (  stream_property(_, file_name(File)) -> write(open) ; write(closed) ),\n\
+                 nl.\n\
+             :- initialization(test).\n",
+            file.display()
+        ),
+        "lazy\nfailed\nclosed\n",
+    );
+
+    fs::remove_file(&file).unwrap();
+}
+
+#[test]
+fn char_conversions_are_per_machine() {
+    let (mut converting, _) = build_machine(|builder| builder);
+
+    converting.run_directive("char_conversion(b, c)").unwrap();
+
+    load_test(
+        |builder| builder,
+        ":- use_module(library(charsio)).\n\
+         :- initialization((set_prolog_flag(char_conversion, on),\n\
+                            read_term_from_chars(\"f(b).\", T),\n\
+                            writeq(T), nl)).\n",
+        "f(b)\n",
+    );
+}
+
+#[test]
+fn write_stream_forms() {
+    let output = Stream::from(String::new());
+    let error = Stream::from(String::new());
+
+    let mut wam = MachineBuilder::new(Stream::from(""), output.clone(), error.clone())
+        .build()
+        .unwrap();
+
+    wam.load_file(
+        "write_stream_forms.pl".into(),
+        Stream::from(
+            ":- initialization((write('a b'), nl, writeq('a b'), nl, writeln(f('X', \"y\")))).\n\
+             :- initialization((write(user_error, 'a b'), nl(user_error),\n\
+                                writeq(user_error, 'a b'), nl(user_error),\n\
+                                writeln(user_error, done))).\n",
+        ),
+    );
+
+    assert_eq!(output_string(&output), "a b\n'a b'\nf(X,[y])\n");
+
+    assert_eq!(output_string(&error), "a b\n'a b'\ndone\n");
+}
+
+#[test]
+fn defined_predicates() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    wam.load_file(
+        "defined_predicates.pl".into(),
+        Stream::from(
+            ":- use_module(library(lists)).\n\
+             :- dynamic(counter/1).\n\
+             counter(0).\n\
+             greet(Name) :- write(hello(Name)).\n",
+        ),
+    );
+
+    let predicates = wam.defined_predicates();
+
+    assert!(predicates.contains(&("greet".to_string(), 1, PredicateKind::Static)));
+    assert!(predicates.contains(&("counter".to_string(), 1, PredicateKind::Dynamic)));
+    assert!(predicates.contains(&(
+        "append".to_string(),
+        3,
+        PredicateKind::Module("lists".to_string())
+    )));
+    assert!(!predicates.iter().any(|(name, _, _)| name == "write"));
+}
+
+#[test]
+fn module_exports() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    wam.load_file(
+        "shapes.pl".into(),
+        Stream::from(
+            ":- module(shapes, [area/2, perimeter/2]).\n\
+             :- use_module(library(lists)).\n\
+             area(square(S), A) :- A is S * S.\n\
+             perimeter(square(S), P) :- P is 4 * S.\n\
+             side(square(S), S).\n",
+        ),
+    );
+
+    let module_names = wam.module_names();
+
+    assert!(module_names.contains(&"lists".to_string()));
+    assert!(module_names.contains(&"shapes".to_string()));
+
+    assert_eq!(
+        wam.module_exports("shapes"),
+        Some(vec![("area".to_string(), 2), ("perimeter".to_string(), 2)])
+    );
+
+    let lists_exports = wam.module_exports("lists").unwrap();
+
+    assert!(lists_exports.contains(&("append".to_string(), 3)));
+    assert!(lists_exports.contains(&("member".to_string(), 2)));
+
+    assert_eq!(wam.module_exports("no_such_module"), None);
+}
+
+#[test]
+fn abort_handle() {
+    let (mut wam, output) = build_machine(|builder| builder);
+
+    let handle = wam.abort_handle();
+
+    let aborter = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        handle.abort();
+    });
+
+    let start = std::time::Instant::now();
+
+    wam.load_file(
+        "abort_handle.pl".into(),
+        Stream::from(
+            "spin(N) :- N1 is N + 1, spin(N1).\n\
+             :- initialization(spin(0)).\n",
+        ),
+    );
+
+    aborter.join().unwrap();
+
+    assert!(wam.was_aborted());
+    assert!(start.elapsed() < std::time::Duration::from_secs(10));
+
+    wam.load_file(
+        "after_abort.pl".into(),
+        Stream::from(":- initialization((write(still_usable), nl)).\n"),
+    );
+
+    assert!(!wam.was_aborted());
+
+    assert_eq!(output_string(&output), "still_usable\n");
+
+    // an abort requested while the machine is idle is dropped.
+    wam.abort_handle().abort();
+    assert!(wam.run_directive("true").is_ok());
+
+    // the abort cannot be caught for good, but cleanup goals run.
+    wam.consult_string(
+        ":- dynamic(cleaned/0).\n\
+         spin(N) :- N1 is N + 1, spin(N1).\n",
+    )
+    .unwrap();
+
+    let handle = wam.abort_handle();
+
+    let aborter = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        handle.abort();
+    });
+
+    let error = wam
+        .run_directive("catch(setup_call_cleanup(true, spin(0), assertz(cleaned)), _, true)")
+        .unwrap_err();
+
+    aborter.join().unwrap();
+
+    assert_eq!(error.name().unwrap().as_str(), "$aborted");
+    assert!(wam.was_aborted());
+    assert!(wam.run_directive("cleaned").is_ok());
+}
+
+#[test]
+fn run_directive() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    assert!(wam.run_directive("assertz(fact(1))").is_ok());
+    assert!(wam.run_directive("fact(1).").is_ok());
+
+    let error = wam.run_directive("atom_length(_, _)").unwrap_err();
+
+    assert_eq!(error.name().unwrap().as_str(), "error");
+    assert_eq!(error.arity(), 2);
+    assert_eq!(
+        error.first_arg().and_then(Term::name).unwrap().as_str(),
+        "instantiation_error"
+    );
+
+    let error = wam.run_directive("fact(2)").unwrap_err();
+
+    assert_eq!(error.name().unwrap().as_str(), "failed");
+    assert_eq!(
+        error.first_arg().and_then(Term::name).unwrap().as_str(),
+        "fact"
+    );
+
+    let error = wam
+        .run_directive("current_output(S), throw(S)")
+        .unwrap_err();
+
+    assert_eq!(error.name().unwrap().as_str(), "error");
+    assert_eq!(
+        error.first_arg().and_then(Term::name).unwrap().as_str(),
+        "representation_error"
+    );
+}
+
+#[test]
+fn api_queries_release_the_heap() {
+    let (mut wam, _) = build_machine(|builder| {
+        builder.with_resource_limits(ResourceLimits {
+            max_heap_cells: Some(2_000_000),
+            ..ResourceLimits::default()
+        })
+    });
+
+    assert!(wam.run_directive("use_module(library(iso_ext))").is_ok());
+    assert!(wam.run_directive("bb_put(kept, f(x))").is_ok());
+    assert!(wam.run_directive("bb_b_put(dropped, f(y))").is_ok());
+
+    for _ in 0..50 {
+        assert!(wam.run_directive("length(_, 100000)").is_ok());
+        assert_eq!(wam.run_query_iter("length(_, 100000)").count(), 1);
+        assert!(wam
+            .run_query_iter("length(_, 100000), throw(oops)")
+            .next()
+            .unwrap()
+            .is_err());
+        assert!(wam
+            .consult_string("big(L) :- length(L, 100000).\n:- big(_).\n")
+            .is_ok());
+    }
+
+    assert!(wam.run_directive("bb_get(kept, f(x))").is_ok());
+    assert!(wam.run_directive("bb_get(dropped, _)").is_err());
+}
+
+#[test]
+fn consult_string() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    assert_eq!(wam.consult_string("p(1).\n").unwrap(), vec![]);
+    assert_eq!(
+        wam.consult_string("p(2).\n").unwrap(),
+        vec![LoadWarning::Overwriting("p".to_string(), 1)]
+    );
+    assert!(wam.run_directive("p(2)").is_ok());
+
+    let LoadError(error) = wam.consult_string("broken(.\n").unwrap_err();
+    assert_eq!(error.name().unwrap().as_str(), "error");
+}
+
+#[test]
+fn run_query_iter() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    let solutions: Vec<_> = wam
+        .run_query_iter("member(X, [1, f(a)]), Y = [X]")
+        .collect();
+
+    assert_eq!(solutions.len(), 2);
+
+    let first = solutions[0].as_ref().unwrap();
+    assert!(matches!(first["X"], Term::Constant(..)));
+    assert!(matches!(first["Y"], Term::Cons(..)));
+
+    let second = solutions[1].as_ref().unwrap();
+    assert_eq!(second["X"].name().unwrap().as_str(), "f");
+    assert_eq!(
+        second["X"]
+            .first_arg()
+            .and_then(Term::name)
+            .unwrap()
+            .as_str(),
+        "a"
+    );
+
+    assert_eq!(wam.run_query_iter("fail").count(), 0);
+
+    {
+        let mut solutions = wam.run_query_iter("X = f(X)");
+        let error = solutions.next().unwrap().unwrap_err();
+        assert_eq!(error.name().unwrap().as_str(), "cyclic_term");
+        assert!(solutions.next().is_none());
+    }
+
+    {
+        let mut solutions = wam.run_query_iter("atom_length(_, _)");
+        let error = solutions.next().unwrap().unwrap_err();
+        assert_eq!(error.name().unwrap().as_str(), "error");
+        assert!(solutions.next().is_none());
+    }
+
+    // the answers are found one at a time, so there can be infinitely
+    // many of them.
+    assert_eq!(wam.run_query_iter("repeat").take(3).count(), 3);
+
+    let lengths: Vec<_> = wam
+        .run_query_iter("length(Xs, N)")
+        .take(3)
+        .map(|solution| solution.unwrap()["N"].clone())
+        .collect();
+
+    assert!(matches!(lengths[2], Term::Constant(_, Constant::Fixnum(2))));
+
+    // an exception keeps the answers found before it.
+    let solutions: Vec<_> = wam
+        .run_query_iter("member(X, [1, 2]), X > 1, throw(oops)")
+        .collect();
+
+    assert_eq!(solutions.len(), 1);
+    assert_eq!(
+        solutions[0].as_ref().unwrap_err().name().unwrap().as_str(),
+        "oops"
+    );
+
+    let solutions: Vec<_> = wam
+        .run_query_iter("member(X, [1, 2]), (X > 1 -> throw(oops) ; true)")
+        .collect();
+
+    assert_eq!(solutions.len(), 2);
+    assert!(solutions[0].is_ok());
+    assert!(solutions[1].is_err());
+
+    assert!(wam.run_directive("true").is_ok());
+}
+
+#[test]
+fn run_directive_with_timeout() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    let result = wam.run_directive_with_timeout("repeat, fail", Duration::from_millis(50));
+    assert!(matches!(result, Err(SessionError::Timeout)));
+
+    // catching the error does not let the goal go on.
+    let result = wam.run_directive_with_timeout(
+        "catch((repeat, fail), _, true), repeat, fail",
+        Duration::from_millis(50),
+    );
+    assert!(matches!(result, Err(SessionError::Timeout)));
+
+    assert!(matches!(
+        wam.run_directive_with_timeout("true", Duration::from_secs(10)),
+        Ok(Ok(()))
+    ));
+    assert!(matches!(
+        wam.run_directive_with_timeout("fail", Duration::from_secs(10)),
+        Ok(Err(_))
+    ));
+    assert!(wam.run_directive("assertz(fact(1)), fact(1)").is_ok());
+}
+
+#[test]
+fn global_inference_limit() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    wam.consult_string("count(0) :- !.\ncount(N) :- N1 is N - 1, count(N1).\n")
+        .unwrap();
+
+    wam.set_global_inference_limit(Some(10_000));
+
+    let error = wam.run_directive("repeat, fail").unwrap_err();
+
+    assert_eq!(error.name().unwrap().as_str(), "error");
+    assert_eq!(
+        error
+            .first_arg()
+            .and_then(Term::first_arg)
+            .and_then(Term::name)
+            .unwrap()
+            .as_str(),
+        "inference_limit"
+    );
+
+    // the spent budget is not renewed by the next query.
+    assert!(wam.run_directive("repeat, fail").is_err());
+
+    // the limit stays in force for the handlers of the error.
+    wam.set_global_inference_limit(Some(10_000));
+    assert!(wam
+        .run_directive("catch((repeat, fail), _, (repeat, fail))")
+        .is_err());
+
+    wam.set_global_inference_limit(Some(10_000));
+    assert!(wam
+        .run_directive("repeat, catch((repeat, fail), _, true), fail")
+        .is_err());
+
+    wam.set_global_inference_limit(Some(10_000));
+    assert!(wam
+        .run_directive("catch((repeat, fail), error(E, _), true), E = resource_error(_)")
+        .is_ok());
+
+    // the budget is shared by the queries of the machine.
+    let count = "count(1000)";
+
+    wam.set_global_inference_limit(Some(5_000));
+    assert!(wam.run_directive(count).is_ok());
+    assert!((0..10).any(|_| wam.run_directive(count).is_err()));
+
+    wam.reset_dynamic_db();
+    assert!(wam.run_directive(count).is_ok());
+
+    wam.set_global_inference_limit(None);
+    assert!((0..10).all(|_| wam.run_directive(count).is_ok()));
+}
+
+#[test]
+fn consult_incremental() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    wam.consult_incremental("edge(a, b).\nedge(b, 'c. ")
+        .unwrap();
+    wam.consult_incremental("d'). % a comment.\npath(X, Y) :- edge(X, Y")
+        .unwrap();
+
+    assert!(wam.run_directive("edge(a, b)").is_ok());
+    assert!(wam.run_directive("edge(b, 'c. d')").is_ok());
+    assert!(wam.run_directive("path(a, b)").is_err());
+
+    wam.consult_incremental(").\n").unwrap();
+
+    assert!(wam.run_directive("path(a, b)").is_ok());
+
+    // a last clause not followed by layout text waits for the finish.
+    wam.consult_incremental("edge(c, d).").unwrap();
+    assert!(wam.run_directive("edge(c, d)").is_err());
+
+    wam.finish_consult_incremental().unwrap();
+
+    assert!(wam.run_directive("edge(a, b)").is_ok());
+    assert!(wam.run_directive("edge(c, d)").is_ok());
+
+    let LoadError(error) = wam.consult_incremental("broken(.\n").unwrap_err();
+    assert_eq!(error.name().unwrap().as_str(), "error");
+}
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn output_writers() {
+    let output = SharedBuffer::default();
+    let error = SharedBuffer::default();
+
+    let (mut wam, _) = build_machine(|builder| {
+        builder
+            .with_output_writer(output.clone())
+            .with_error_writer(error.clone())
+    });
+
+    wam.run_directive("write(hello), nl").unwrap();
+    wam.run_directive("write(user_error, oops)").unwrap();
+
+    assert_eq!(output.0.borrow().as_slice(), b"hello\n");
+    assert_eq!(error.0.borrow().as_slice(), b"oops");
+}
+
+#[test]
+fn register_foreign_rust_predicate() {
+    let (mut wam, _) = build_machine(|builder| builder);
+
+    wam.register_foreign_rust_predicate(
+        "add_one",
+        2,
+        Box::new(
+            |machine_st: &mut MachineState, args: &[Addr]| match args[0] {
+                Addr::Fixnum(n) => machine_st.unify_addrs(args[1], Addr::Fixnum(n + 1)),
+                _ => false,
+            },
+        ),
+    );
+
+    assert!(wam.run_directive("add_one(1, 2)").is_ok());
+    assert!(wam.run_directive("add_one(1, 3)").is_err());
+    assert!(wam.run_directive("add_one(a, _)").is_err());
+
+    let solutions: Vec<_> = wam.run_query_iter("add_one(41, X)").collect();
+
+    assert_eq!(solutions.len(), 1);
+    assert!(matches!(
+        solutions[0].as_ref().unwrap()["X"],
+        Term::Constant(_, Constant::Fixnum(42))
+    ));
+
+    wam.register_foreign_rust_predicate(
+        "add_one",
+        2,
+        Box::new(
+            |machine_st: &mut MachineState, args: &[Addr]| match args[0] {
+                Addr::Fixnum(n) => machine_st.unify_addrs(args[1], Addr::Fixnum(n + 2)),
+                _ => false,
+            },
+        ),
+    );
+
+    assert!(wam.run_directive("add_one(1, 3)").is_ok());
+    assert!(wam.run_directive("add_one(1, 2)").is_err());
+}
+
+#[test]
+fn initial_ops() {
+    let (mut wam, _) = build_machine(|builder| {
+        builder.with_initial_ops(vec![
+            OpDecl::new(700, XFX, clause_name!("===>")),
+            OpDecl::new(0, YFX, clause_name!("rdiv")),
+        ])
+    });
+
+    assert!(wam.run_directive("X = (a ===> b), X = ===>(a, b)").is_ok());
+    assert!(wam.run_directive("X = (1 rdiv 2)").is_err());
+
+    let result = MachineBuilder::new(
+        Stream::from(""),
+        Stream::from(String::new()),
+        Stream::from(String::new()),
+    )
+    .with_initial_ops(vec![OpDecl::new(200, XF, clause_name!("+"))])
+    .build();
+
+    assert!(matches!(result, Err(SessionError::OpIsInfixAndPostFix(_))));
+
+    let result = MachineBuilder::new(
+        Stream::from(""),
+        Stream::from(String::new()),
+        Stream::from(String::new()),
+    )
+    .with_initial_ops(vec![OpDecl::new(0, XFX, clause_name!(":-"))])
+    .build();
+
+    assert!(result.is_err());
+}
@@ -1,4 +1,5 @@
 mod helper;
 
 mod issues;
+mod machine;
 mod src_tests;
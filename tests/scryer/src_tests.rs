@@ -1,4 +1,9 @@
-use crate::helper::{load_module_test, run_top_level_test_with_args};
+use crate::helper::{load_module_test, run_top_level_test_no_args, run_top_level_test_with_args};
+
+#[test]
+fn arithmetic() {
+    load_module_test("src/tests/arithmetic.pl", "");
+}
 
 #[test]
 fn builtins() {
@@ -10,6 +15,19 @@ fn call_with_inference_limit() {
     load_module_test("src/tests/call_with_inference_limit.pl", "");
 }
 
+#[test]
+fn error() {
+    load_module_test("src/tests/error.pl", "");
+}
+
+#[test]
+fn expansion_error() {
+    load_module_test(
+        "tests-pl/expansion_error.pl",
+        "expansion_error(broken,oops)\nlocated\n",
+    );
+}
+
 #[test]
 fn facts() {
     load_module_test("src/tests/facts.pl", "");
@@ -20,6 +38,26 @@ fn hello_world() {
     load_module_test("src/tests/hello_world.pl", "Hello World!\n");
 }
 
+#[test]
+fn lists() {
+    load_module_test("src/tests/lists.pl", "");
+}
+
+#[test]
+fn ordsets() {
+    load_module_test("src/tests/ordsets.pl", "");
+}
+
+#[test]
+fn dicts() {
+    load_module_test("src/tests/dicts.pl", "");
+}
+
+#[test]
+fn random() {
+    load_module_test("src/tests/random.pl", "");
+}
+
 #[test]
 fn syntax_error() {
     load_module_test(
@@ -28,12 +66,209 @@ fn syntax_error() {
     );
 }
 
+#[test]
+fn assert_goal_expansion() {
+    load_module_test(
+        "tests-pl/assert_goal_expansion.pl",
+        "real_goal(1)\nexpanded\n",
+    );
+}
+
+#[test]
+fn bom() {
+    load_module_test("tests-pl/bom.pl", "a\n");
+}
+
+#[test]
+fn stream_to_lazy_list() {
+    load_module_test("tests-pl/stream_to_lazy_list.pl", "unread\n[a,b]\n");
+}
+
+#[test]
+fn read_term_double_quotes() {
+    load_module_test(
+        "tests-pl/read_term_double_quotes.pl",
+        "[97,98,99]\n[a,b,c]\nunchanged\ninstantiation_error\n",
+    );
+}
+
+#[test]
+fn read_term_variable_names() {
+    load_module_test(
+        "tests-pl/read_term_variable_names.pl",
+        "bound\nend_of_file-[]\n",
+    );
+}
+
+#[test]
+fn read_term_from_atom() {
+    load_module_test(
+        "tests-pl/read_term_from_atom.pl",
+        "a\nvariable_names\nread_term_from_atom/3\n",
+    );
+}
+
+#[test]
+fn tabling() {
+    load_module_test("tests-pl/tabling.pl", "[a,b,c,d]\nno_duplicates\n");
+}
+
+#[test]
+fn term_hash() {
+    load_module_test(
+        "tests-pl/term_hash.pl",
+        "equal\ndistinct\nunbound\ndepth_limited\ncyclic\ncyclic_depth_limited\nintegers_distinct\n",
+    );
+}
+
+#[test]
+fn use_module_path() {
+    load_module_test(
+        "tests-pl/use_module_path.pl",
+        "hello\nexistence_error(source_sink,modules/missing)\nhello\n",
+    );
+}
+
+#[test]
+fn write_term_options() {
+    load_module_test(
+        "tests-pl/write_term_options.pl",
+        "a+b*c .\na+b*c\na*(b+c)\nf(a, g(b, c))\nf(a,b)\n",
+    );
+}
+
+#[test]
+fn write_cycles() {
+    load_module_test("tests-pl/write_cycles.pl", "rejected\nelided\nf(a)\n");
+}
+
+#[test]
+fn writeq_quoting() {
+    load_module_test(
+        "tests-pl/writeq_quoting.pl",
+        "'hello world'\n'Atom'\n[]\n{}\n'it\\'s'\n''\n'\\'\\''\n'\\x1b\\'\n','\n'|'\n",
+    );
+}
+
+#[test]
+fn copy_term_residuals() {
+    load_module_test("tests-pl/copy_term_residuals.pl", "1\nsingle_dif\n");
+}
+
+#[test]
+fn clpz_label() {
+    load_module_test("tests-pl/clpz_label.pl", "[1-3,2-2,3-1]\ninconsistent\n");
+}
+
+#[test]
+fn trace_ports() {
+    run_top_level_test_no_args(
+        "\
+        use_module(library(debug)).\n\
+        assertz(p(1)).\n\
+        assertz((q(X) :- p(X))).\n\
+        trace.\n\
+        once(q(1)).\n\
+        once(q(2)).\n\
+        notrace.\n\
+        ",
+        "   \
+        true.\n   \
+        true.\n   \
+        true.\n   \
+        true.\n\
+        call: q(1)\n\
+        call: p(1)\n\
+        exit: p(1)\n\
+        exit: q(1)\n   \
+        true.\n\
+        call: q(2)\n\
+        call: p(2)\n\
+        fail: p(2)\n\
+        fail: q(2)\n\
+        false.\n   \
+        true.\n\
+        ",
+    );
+}
+
+#[test]
+fn trace_hook() {
+    run_top_level_test_no_args(
+        "\
+        ['tests-pl/trace_hook.pl'].\n\
+        leash([call]).\n\
+        trace.\n\
+        once(len([a,b,c], N)).\n\
+        notrace.\n\
+        findall(Xs, called(Xs), Calls).\n\
+        ",
+        "   \
+        true.\n   \
+        true.\n   \
+        true.\n   \
+        N = 3.\n   \
+        true.\n   \
+        Calls = [[a,b,c],[b,c],[c],[]].\n\
+        ",
+    );
+}
+
+#[test]
+fn trace_cut() {
+    run_top_level_test_no_args(
+        "\
+        ['tests-pl/trace_cut.pl'].\n\
+        findall(Z, debug:'$trace_goal'(max(3, 1, Z)), Zs).\n\
+        findall(X, debug:'$trace_goal'(first([a, b], X)), Xs).\n\
+        findall(X, debug:'$trace_goal'(local(X)), Xs).\n\
+        ",
+        "   \
+        true.\n   \
+        Zs = [3].\n   \
+        Xs = [a].\n   \
+        Xs = [a,c].\n\
+        ",
+    );
+}
+
+#[test]
+fn syntax_error_location() {
+    load_module_test(
+        "tests-pl/invalid_single_quoted_char.pl",
+        "invalid_single_quoted_character-0\n",
+    );
+}
+
+#[test]
+fn op3_infix_and_postfix() {
+    load_module_test(
+        "tests-pl/op3-infix-postfix.pl",
+        "caught: error(permission_error(create,operator,isa),load/1)\n",
+    );
+
+    load_module_test(
+        "tests-pl/op3-postfix-infix.pl",
+        "caught: error(permission_error(create,operator,isa),load/1)\n",
+    );
+}
+
+#[test]
+fn op3_prefix_and_infix() {
+    load_module_test("tests-pl/op3-prefix-infix.pl", "");
+}
+
 #[test]
 #[ignore] // fails to halt
 fn predicates() {
     load_module_test("src/tests/predicates.pl", "");
 }
 
+#[test]
+fn retract() {
+    load_module_test("src/tests/retract.pl", "");
+}
+
 #[test]
 fn rules() {
     load_module_test("src/tests/rules.pl", "");